@@ -1,3 +1,9 @@
 // src-tauri/src/api.rs
 pub mod rate_limiter;
 pub mod discord;
+pub mod search;
+pub mod export;
+pub mod package;
+pub mod plan;
+pub mod schedule;
+pub mod policy;
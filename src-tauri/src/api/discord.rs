@@ -3,12 +3,19 @@
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Manager, Emitter};
 use crate::api::rate_limiter::ApiHandle;
+use crate::api::search::{self, SearchPage, SearchQuery, SearchScope};
+use crate::auth::session;
 use crate::core::error::AppError;
 use crate::core::vault::Vault;
-use crate::core::op_manager::OperationManager;
+use crate::core::op_manager::{OperationManager, OperationState, OperationStatus};
+use crate::core::journal::{ActionError, JobJournal, JobReport, JobStatus, PurgeFilters};
 use crate::core::logger::Logger;
+use crate::core::snowflake::{self, SnowflakeWindow};
+use crate::core::archive::MessageArchive;
+use crate::core::attachments::AttachmentStore;
+use crate::core::plan::{self, PlannedAction, PurgePlan};
 use std::time::Duration;
-use std::sync::atomic::Ordering;
+use std::collections::HashSet;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Guild {
@@ -23,8 +30,21 @@ pub struct Channel {
     pub name: Option<String>,
     #[serde(rename = "type")]
     pub channel_type: u8,
+    /// Category of a channel, or the channel a thread lives in.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Resolved name of the parent, filled in for threads.
+    #[serde(default)]
+    pub parent_name: Option<String>,
 }
 
+/// Channel types whose history can be read and purged: text, voice text chat, announcement,
+/// announcement/public/private threads and stage.
+const MESSAGE_CHANNEL_TYPES: [u8; 7] = [0, 2, 5, 10, 11, 12, 13];
+/// Channel types that can own threads. Forum (15) and media (16) channels hold no messages of
+/// their own, so they only contribute their posts (threads) to the listing.
+const THREAD_PARENT_TYPES: [u8; 4] = [0, 5, 15, 16];
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Relationship {
     pub id: String,
@@ -34,16 +54,62 @@ pub struct Relationship {
     pub rel_type: u8,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct OperationStatus {
-    pub is_running: bool,
-    pub is_paused: bool,
-    pub should_abort: bool,
+/// Result of evaluating a single message during a purge, reported in `deletion_progress` events.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageOutcome {
+    Deleted,
+    Simulated,
+    SkippedNotOurs,
+    Forbidden,
+    /// The message no longer exists (HTTP 404).
+    AlreadyGone,
+    Failed,
+}
+
+/// One message addressed directly by id, e.g. from a data package or a previous export.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MessageTarget {
+    pub channel_id: String,
+    pub message_id: String,
+}
+
+/// Tally of a `delete_message_ids` run.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct TargetedDeleteReport {
+    pub deleted: u64,
+    pub already_gone: u64,
+    pub forbidden: u64,
+    pub failed: u64,
+    /// Only set by `execute_plan`.
+    pub reactions_removed: u64,
+    /// Only set by `execute_plan`; reactions that are already gone are not failures.
+    pub reactions_failed: u64,
+    pub aborted: bool,
+}
+
+impl TargetedDeleteReport {
+    pub(crate) fn count(&mut self, outcome: MessageOutcome) {
+        match outcome {
+            MessageOutcome::Deleted => self.deleted += 1,
+            MessageOutcome::AlreadyGone => self.already_gone += 1,
+            MessageOutcome::Forbidden => self.forbidden += 1,
+            _ => self.failed += 1,
+        }
+    }
+
+    pub(crate) fn count_reaction(&mut self, outcome: MessageOutcome) {
+        match outcome {
+            MessageOutcome::Deleted => self.reactions_removed += 1,
+            MessageOutcome::AlreadyGone => {}
+            _ => self.reactions_failed += 1,
+        }
+    }
 }
 
 #[tauri::command]
 pub async fn fetch_guilds(app_handle: AppHandle) -> Result<Vec<Guild>, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let api_handle = app_handle.state::<ApiHandle>();
     Logger::info(&app_handle, &format!("[SYNC] Fetching guilds (OAuth: {})...", is_bearer), None);
     
@@ -61,7 +127,7 @@ pub async fn fetch_guilds(app_handle: AppHandle) -> Result<Vec<Guild>, AppError>
 
 #[tauri::command]
 pub async fn fetch_channels(app_handle: AppHandle, guild_id: Option<String>) -> Result<Vec<Channel>, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let api_handle = app_handle.state::<ApiHandle>();
     
     if let Some(gid) = guild_id {
@@ -75,7 +141,13 @@ pub async fn fetch_channels(app_handle: AppHandle, guild_id: Option<String>) ->
             return Err(AppError { user_message: format!("Mapping failed (HTTP {})", status), technical_details: Some(body), ..Default::default() }); 
         }
         let channels: Vec<Channel> = response.json().await?;
-        Ok(channels.into_iter().filter(|c| c.channel_type == 0 || c.channel_type == 11 || c.channel_type == 12).collect())
+        let mut result: Vec<Channel> = channels.iter().filter(|c| MESSAGE_CHANNEL_TYPES.contains(&c.channel_type)).cloned().collect();
+        for mut thread in fetch_guild_threads(&app_handle, &api_handle, &token, is_bearer, &gid, &channels).await? {
+            if result.iter().any(|c| c.id == thread.id) { continue; }
+            thread.parent_name = channels.iter().find(|c| Some(&c.id) == thread.parent_id.as_ref()).and_then(|c| c.name.clone());
+            result.push(thread);
+        }
+        Ok(result)
     } else {
         Logger::info(&app_handle, "[SYNC] Fetching DM nodes...", None);
         if is_bearer { return Err(AppError { user_message: "DMs restricted in Official Gate.".into(), ..Default::default() }); }
@@ -90,20 +162,59 @@ pub async fn fetch_channels(app_handle: AppHandle, guild_id: Option<String>) ->
             let ch_type = ch["type"].as_u64().unwrap_or(0);
             if ch_type == 1 || ch_type == 3 {
                 let name = if ch_type == 1 {
-                    ch["recipients"].as_array().and_then(|r| r.get(0)).and_then(|u| u["username"].as_str()).map(|s| format!("DM with {}", s))
+                    ch["recipients"].as_array().and_then(|r| r.first()).and_then(|u| u["username"].as_str()).map(|s| format!("DM with {}", s))
                 } else {
                     ch["name"].as_str().map(|s| s.to_string()).or_else(|| Some("Unnamed Group DM".to_string()))
                 };
-                result.push(Channel { id: ch["id"].as_str().unwrap_or_default().to_string(), name, channel_type: ch_type as u8 });
+                result.push(Channel { id: ch["id"].as_str().unwrap_or_default().to_string(), name, channel_type: ch_type as u8, parent_id: None, parent_name: None });
             }
         }
         Ok(result)
     }
 }
 
+/// Lists the guild's active threads plus the archived public threads and the archived private
+/// threads we joined under every thread-capable channel. Parents we cannot read are skipped.
+async fn fetch_guild_threads(app_handle: &AppHandle, api_handle: &ApiHandle, token: &str, is_bearer: bool, guild_id: &str, channels: &[Channel]) -> Result<Vec<Channel>, AppError> {
+    let mut threads = Vec::new();
+    let response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/guilds/{}/threads/active", guild_id), None, token, is_bearer).await?;
+    if response.status().is_success() {
+        let body: serde_json::Value = response.json().await?;
+        threads.extend(serde_json::from_value::<Vec<Channel>>(body["threads"].clone()).unwrap_or_default());
+    } else {
+        Logger::debug(app_handle, &format!("[SYNC] Active thread listing unavailable for guild {} (HTTP {})", guild_id, response.status()), None);
+    }
+
+    for parent in channels.iter().filter(|c| THREAD_PARENT_TYPES.contains(&c.channel_type)) {
+        let archives = [
+            (format!("https://discord.com/api/v9/channels/{}/threads/archived/public", parent.id), false),
+            (format!("https://discord.com/api/v9/channels/{}/users/@me/threads/archived/private", parent.id), true),
+        ];
+        for (base_url, paged_by_id) in archives {
+            let mut before: Option<String> = None;
+            loop {
+                let url = match &before {
+                    Some(before) => format!("{}?limit=100&before={}", base_url, before),
+                    None => format!("{}?limit=100", base_url),
+                };
+                let response = api_handle.send_request(reqwest::Method::GET, &url, None, token, is_bearer).await?;
+                if !response.status().is_success() { break; }
+                let body: serde_json::Value = response.json().await?;
+                let page = body["threads"].as_array().cloned().unwrap_or_default();
+                // Public archives page by archive timestamp, joined private archives by thread id.
+                let next = page.last().and_then(|t| if paged_by_id { t["id"].as_str() } else { t["thread_metadata"]["archive_timestamp"].as_str() }).map(|s| s.to_string());
+                threads.extend(page.into_iter().filter_map(|t| serde_json::from_value::<Channel>(t).ok()));
+                if !body["has_more"].as_bool().unwrap_or(false) || next.is_none() || next == before { break; }
+                before = next;
+            }
+        }
+    }
+    Ok(threads)
+}
+
 #[tauri::command]
 pub async fn fetch_relationships(app_handle: AppHandle) -> Result<Vec<Relationship>, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let api_handle = app_handle.state::<ApiHandle>();
     if is_bearer { return Err(AppError { user_message: "Relationships restricted in Official Gate.".into(), ..Default::default() }); }
     
@@ -115,40 +226,42 @@ pub async fn fetch_relationships(app_handle: AppHandle) -> Result<Vec<Relationsh
 
 #[tauri::command]
 pub async fn bulk_remove_relationships(app_handle: AppHandle, window: tauri::Window, user_ids: Vec<String>) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
+    let op = op_manager.begin(&app_handle, "remove_relationships", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     for (i, user_id) in user_ids.iter().enumerate() {
-        op_manager.state.wait_if_paused().await;
-        if op_manager.state.should_abort.load(Ordering::SeqCst) { break; }
+        op.wait_if_paused().await;
+        if op.is_aborted() { break; }
 
         let url = format!("https://discord.com/api/v9/users/@me/relationships/{}", user_id);
         let _ = api_handle.send_request(reqwest::Method::DELETE, &url, None, &token, is_bearer).await;
-        let _ = window.emit("relationship_progress", serde_json::json!({ "current": i + 1, "total": user_ids.len(), "id": user_id, "status": "severing" }));
+        op.set_progress(i as u64 + 1, user_ids.len() as u64);
+        let _ = window.emit("relationship_progress", serde_json::json!({ "job_id": op.job_id, "current": i + 1, "total": user_ids.len(), "id": user_id, "status": "severing" }));
     }
-    op_manager.state.reset();
+    drop(op);
     let _ = window.emit("relationship_complete", ());
     Ok(())
 }
 
 #[tauri::command]
 pub async fn bulk_leave_guilds(app_handle: AppHandle, window: tauri::Window, guild_ids: Vec<String>) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
+    let op = op_manager.begin(&app_handle, "leave_guilds", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     for (i, guild_id) in guild_ids.iter().enumerate() {
-        op_manager.state.wait_if_paused().await;
-        if op_manager.state.should_abort.load(Ordering::SeqCst) { break; }
+        op.wait_if_paused().await;
+        if op.is_aborted() { break; }
 
         let url = format!("https://discord.com/api/v9/users/@me/guilds/{}", guild_id);
         let _ = api_handle.send_request(reqwest::Method::DELETE, &url, None, &token, is_bearer).await;
-        let _ = window.emit("leave_progress", serde_json::json!({ "current": i + 1, "total": guild_ids.len(), "id": guild_id, "status": "severing" }));
+        op.set_progress(i as u64 + 1, guild_ids.len() as u64);
+        let _ = window.emit("leave_progress", serde_json::json!({ "job_id": op.job_id, "current": i + 1, "total": guild_ids.len(), "id": guild_id, "status": "severing" }));
     }
-    op_manager.state.reset();
+    drop(op);
     let _ = window.emit("leave_complete", ());
     Ok(())
 }
@@ -158,102 +271,542 @@ pub async fn bulk_delete_messages(
     app_handle: AppHandle,
     window: tauri::Window,
     channel_ids: Vec<String>,
-    start_time: Option<u64>,
-    end_time: Option<u64>,
-    search_query: Option<String>,
-    purge_reactions: bool,
-    simulation: bool,
-    only_attachments: bool,
+    filters: PurgeFilters,
 ) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
-    let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
+    filters.compile_content_filter()?;
+    let mut journal = JobJournal::new(channel_ids, filters);
+    Logger::info(&app_handle, &format!("[OP] Destructive purge initialized for {} nodes (Sim: {}, Job: {})", journal.channels.len(), journal.filters.simulation, journal.job_id), None);
+    run_purge_job(&app_handle, &window, &mut journal).await
+}
 
-    Logger::info(&app_handle, &format!("[OP] Destructive purge initialized for {} nodes (Sim: {})", channel_ids.len(), simulation), None);
+#[tauri::command]
+pub async fn resume_job(app_handle: AppHandle, window: tauri::Window, job_id: String) -> Result<(), AppError> {
+    let mut journal = JobJournal::load(&app_handle, &job_id)?;
+    if journal.status == JobStatus::Completed {
+        return Err(AppError { user_message: "This job has already completed.".into(), error_code: "job_completed".into(), ..Default::default() });
+    }
+    let remaining = journal.channels.iter().filter(|c| !c.completed).count();
+    Logger::info(&app_handle, &format!("[OP] Resuming job {} ({} nodes remaining, {} items already nullified)", job_id, remaining, journal.deleted_total), None);
+    run_purge_job(&app_handle, &window, &mut journal).await
+}
 
-    let mut deleted_total = 0;
+#[tauri::command]
+pub async fn list_resumable_jobs(app_handle: AppHandle) -> Result<Vec<JobJournal>, AppError> {
+    Ok(JobJournal::list(&app_handle)?.into_iter().filter(|j| j.status != JobStatus::Completed).collect())
+}
 
-    for (i, channel_id) in channel_ids.iter().enumerate() {
-        let mut last_message_id: Option<String> = None;
-        let mut consecutive_failures = 0;
+#[tauri::command]
+pub async fn get_job_report(app_handle: AppHandle, job_id: String) -> Result<JobReport, AppError> {
+    JobJournal::load_report(&app_handle, &job_id)
+}
+
+/// Deletes known messages directly, without paging any history.
+/// Deleting is idempotent, so re-running an interrupted list simply reports the finished part as `already_gone`.
+#[tauri::command]
+pub async fn delete_message_ids(app_handle: AppHandle, window: tauri::Window, targets: Vec<MessageTarget>) -> Result<TargetedDeleteReport, AppError> {
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
+    let op_manager = app_handle.state::<OperationManager>();
+    let op = op_manager.begin(&app_handle, "delete_messages", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
+    Logger::info(&app_handle, &format!("[OP] Targeted purge initialized for {} items", targets.len()), None);
+
+    let mut report = TargetedDeleteReport::default();
+    for (i, target) in targets.iter().enumerate() {
+        op.wait_if_paused().await;
+        if op.is_aborted() {
+            report.aborted = true;
+            break;
+        }
 
-        'message_loop: loop {
-            op_manager.state.wait_if_paused().await;
-            if op_manager.state.should_abort.load(Ordering::SeqCst) { break 'message_loop; }
+        let outcome = if snowflake::parse(&target.channel_id).is_none() || snowflake::parse(&target.message_id).is_none() {
+            MessageOutcome::Failed
+        } else {
+            let url = format!("https://discord.com/api/v9/channels/{}/messages/{}", target.channel_id, target.message_id);
+            let Ok(attempt) = attempt_delete(&api_handle, &url, &token, is_bearer).await else {
+                report.aborted = true;
+                break;
+            };
+            attempt.outcome
+        };
+        report.count(outcome);
+
+        op.set_progress(i as u64 + 1, targets.len() as u64);
+        let _ = window.emit("deletion_progress", serde_json::json!({
+            "job_id": op.job_id,
+            "current": i + 1,
+            "total": targets.len(),
+            "id": target.channel_id,
+            "message_id": target.message_id,
+            "outcome": outcome,
+            "deleted_count": report.deleted,
+            "forbidden_count": report.forbidden,
+            "status": "purging",
+        }));
+    }
+
+    let job_id = op.job_id.clone();
+    drop(op);
+    let _ = window.emit("deletion_complete", serde_json::json!({ "job_id": job_id, "deleted_count": report.deleted, "report": report }));
+    Logger::info(&app_handle, &format!("[OP] Targeted purge finished. Deleted: {}, already gone: {}, forbidden: {}, failed: {}", report.deleted, report.already_gone, report.forbidden, report.failed), None);
+    Ok(report)
+}
 
-            let mut url = format!("https://discord.com/api/v9/channels/{}/messages?limit=100", channel_id);
-            if let Some(before) = &last_message_id { url.push_str(&format!("&before={}", before)); }
+/// Drives a purge job to completion, recording the final status in its journal.
+/// Progress goes to `window`, or to the app handle for jobs started by the scheduler.
+pub(crate) async fn run_purge_job(app_handle: &AppHandle, window: &impl Emitter<tauri::Wry>, journal: &mut JobJournal) -> Result<(), AppError> {
+    let op_manager = app_handle.state::<OperationManager>();
+    // New jobs reach this point unsaved; the journal is only written once the run slot is ours.
+    let op = journal.claim(&op_manager, &JobJournal::dir(app_handle)?)?;
+    OperationManager::announce(app_handle, &op);
+
+    let result = match purge_channels(app_handle, window, &op, journal).await {
+        // An abort that cut a request short ends the job like one noticed between messages.
+        Err(e) if e.is_cancelled() => Ok(false),
+        result => result,
+    };
+    journal.status = match &result {
+        Ok(true) => JobStatus::Completed,
+        Ok(false) => JobStatus::Aborted,
+        Err(_) => JobStatus::Interrupted,
+    };
+    // Reopened DMs stay open while the job can still be resumed.
+    if journal.status == JobStatus::Completed && !journal.reopened_dms.is_empty() {
+        close_dms(app_handle, &std::mem::take(&mut journal.reopened_dms)).await;
+    }
+    if let Err(e) = journal.save(app_handle) {
+        Logger::error(app_handle, "[OP] Failed to persist job journal", Some(serde_json::json!({ "job_id": journal.job_id, "error": e.to_string() })));
+    }
+    let report = journal.save_report(app_handle).unwrap_or_else(|e| {
+        Logger::error(app_handle, "[OP] Failed to persist job report", Some(serde_json::json!({ "job_id": journal.job_id, "error": e.to_string() })));
+        journal.report()
+    });
+
+    let archive = if journal.filters.archive && !journal.filters.simulation {
+        let archived_count = journal.channels.iter().map(|c| c.archived).sum();
+        MessageArchive::open(app_handle).ok().map(|a| a.summary(journal.channels.iter().map(|c| c.channel_id.as_str()), archived_count))
+    } else {
+        None
+    };
 
-            let response = api_handle.send_request(reqwest::Method::GET, &url, None, &token, is_bearer).await?;
-            if !response.status().is_success() { 
-                consecutive_failures += 1;
-                if consecutive_failures > 3 { break; }
-                tokio::time::sleep(Duration::from_secs(1)).await;
-                continue; 
+    if journal.filters.simulation {
+        match PurgePlan::load(app_handle, &journal.job_id, journal.filters.clone()) {
+            Ok(plan) => {
+                let _ = window.emit("plan_ready", serde_json::json!({ "plan_id": plan.plan_id, "status": journal.status, "delete_count": plan.delete_count(), "reaction_count": plan.reaction_count(), "digest": plan.digest }));
             }
+            Err(e) => Logger::error(app_handle, "[OP] Failed to read back simulation plan", Some(serde_json::json!({ "job_id": journal.job_id, "error": e.to_string() }))),
+        }
+    }
+
+    drop(op);
+    let _ = window.emit("job_report", &report);
+    let _ = window.emit("deletion_complete", serde_json::json!({ "job_id": journal.job_id, "status": journal.status, "deleted_count": journal.deleted_total, "archive": archive, "report": report }));
+    Logger::info(app_handle, &format!("[OP] Destructive purge finished ({:?}). Items nullified: {}, already gone: {}, failed: {}, 429 retries: {}", journal.status, journal.deleted_total, report.not_found, report.failed, report.rate_limited_retries), None);
+    if let Some(archive) = &archive {
+        Logger::info(app_handle, &format!("[ARCHIVE] {} items preserved in {} ({} bytes)", archive.archived_count, archive.path, archive.size_bytes), None);
+    }
+    result.map(|_| ())
+}
+
+/// Processes every unfinished channel of the journal, advancing its cursors as messages are handled.
+/// Returns `Ok(false)` if the user aborted before all channels were completed.
+async fn purge_channels(app_handle: &AppHandle, window: &impl Emitter<tauri::Wry>, op: &OperationState, journal: &mut JobJournal) -> Result<bool, AppError> {
+    let (token, is_bearer) = session::active_token(app_handle).await?;
+    let identity = Vault::get_active_identity(app_handle)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
+    let filters = journal.filters.clone();
+    let content_filter = filters.compile_content_filter()?;
+    let time_window = SnowflakeWindow::new(filters.start_time, filters.end_time);
+    let total_channels = journal.channels.len();
+    let archive = if filters.archive && !filters.simulation { Some(MessageArchive::open(app_handle)?) } else { None };
+    let attachment_store = if filters.download_attachments && !filters.simulation {
+        Some(AttachmentStore::open(app_handle, filters.attachment_max_bytes)?)
+    } else {
+        None
+    };
+    let manageable_guilds = if filters.include_manageable {
+        fetch_manageable_guilds(&api_handle, &token, is_bearer).await?
+    } else {
+        HashSet::new()
+    };
+    // Executing the plan later archives and downloads exactly what this run would have.
+    let keep_messages = filters.simulation && (filters.archive || filters.download_attachments);
+
+    for i in 0..total_channels {
+        if journal.channels[i].completed { continue; }
+        op.set_progress(i as u64, total_channels as u64);
+        let channel_id = journal.channels[i].channel_id.clone();
+        let mut consecutive_failures = 0;
+        let scope = if filters.use_search || filters.include_manageable {
+            Some(search::resolve_scope(&api_handle, &token, is_bearer, &channel_id).await?)
+        } else {
+            None
+        };
+        let can_manage = matches!(&scope, Some(SearchScope::Guild { guild_id, .. }) if manageable_guilds.contains(guild_id));
+        let search_scope = scope.filter(|_| filters.use_search);
+
+        loop {
+            op.wait_if_paused().await;
+            if op.is_aborted() { return Ok(false); }
+
+            let page = match &search_scope {
+                Some(scope) => {
+                    let query = SearchQuery {
+                        author_id: &identity.id,
+                        window: time_window,
+                        max_id: journal.channels[i].before.as_deref(),
+                        has_attachment: filters.only_attachments,
+                    };
+                    match search::fetch_page(&api_handle, &token, is_bearer, scope, &query).await? {
+                        SearchPage::Ready(messages) => Ok(messages),
+                        SearchPage::Indexing(wait) => {
+                            Logger::debug(app_handle, &format!("[OP] Search index for node {} not ready, retrying in {:?}", channel_id, wait), None);
+                            tokio::select! {
+                                _ = op.cancel.cancelled() => return Ok(false),
+                                _ = tokio::time::sleep(wait) => continue,
+                            }
+                        }
+                        SearchPage::Failed(status) => {
+                            Logger::warn(app_handle, &format!("[OP] Search failed for node {} (HTTP {})", channel_id, status), None);
+                            Err(status)
+                        }
+                    }
+                }
+                None => {
+                    // Start right below the window's upper bound instead of at the newest message.
+                    let mut url = format!("https://discord.com/api/v9/channels/{}/messages?limit=100", channel_id);
+                    let before = journal.channels[i].before.clone().or_else(|| time_window.max_id.map(|id| id.to_string()));
+                    if let Some(before) = before { url.push_str(&format!("&before={}", before)); }
+
+                    let (response, rate_limited) = api_handle.send_request_counted(reqwest::Method::GET, &url, None, &token, is_bearer).await?;
+                    journal.channels[i].rate_limited_retries += rate_limited as u64;
+                    if response.status().is_success() { Ok(response.json::<Vec<serde_json::Value>>().await?) } else { Err(response.status()) }
+                }
+            };
+
+            let messages = match page {
+                Ok(messages) => messages,
+                // A node we cannot read will not become readable by retrying; it is finished as skipped.
+                Err(status @ (reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::NOT_FOUND)) => {
+                    Logger::warn(app_handle, &format!("[OP] Node {} is not readable (HTTP {}), skipping", channel_id, status), None);
+                    break;
+                }
+                Err(status) => {
+                    consecutive_failures += 1;
+                    if consecutive_failures > 3 {
+                        // Anything else may be transient: the channel stays unfinished so a resume retries it from the cursor.
+                        return Err(AppError {
+                            user_message: format!("History of node {} could not be fetched (HTTP {}).", channel_id, status),
+                            error_code: "history_fetch_failed".into(),
+                            ..Default::default()
+                        });
+                    }
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            };
             consecutive_failures = 0;
 
-            let messages: Vec<serde_json::Value> = response.json().await?;
             if messages.is_empty() { break; }
-            last_message_id = messages.last().and_then(|m| m["id"].as_str().map(|s| s.to_string()));
+            let oldest_id = messages.iter().filter_map(|m| m["id"].as_str().and_then(snowflake::parse)).min();
 
+            let mut planned = Vec::new();
+            let mut unreported = 0;
             for msg in messages {
-                op_manager.state.wait_if_paused().await;
-                if op_manager.state.should_abort.load(Ordering::SeqCst) { break 'message_loop; }
+                op.wait_if_paused().await;
+                if op.is_aborted() {
+                    // The plan must cover every message the saved cursor has moved past.
+                    PurgePlan::append(app_handle, &journal.job_id, &planned)?;
+                    journal.save(app_handle)?;
+                    return Ok(false);
+                }
 
                 let msg_id = msg["id"].as_str().unwrap_or_default();
-                let content = msg["content"].as_str().unwrap_or_default();
-                let timestamp = chrono::DateTime::parse_from_rfc3339(msg["timestamp"].as_str().unwrap_or_default()).map(|dt| dt.timestamp_millis() as u64).unwrap_or(0);
-                
-                let matches_query = if let Some(query) = &search_query { content.to_lowercase().contains(&query.to_lowercase()) } else { true };
-                let has_attachments = msg["attachments"].as_array().map_or(false, |arr| !arr.is_empty());
-
-                if let Some(start) = start_time { if timestamp < start { if last_message_id.is_some() { break 'message_loop; } else { continue; } }}
-                if let Some(end) = end_time { if timestamp > end { continue; }}
-
-                if only_attachments && !has_attachments { continue; }
-
-                if !simulation {
-                    if purge_reactions {
-                        if let Some(reactions) = msg["reactions"].as_array() {
-                            for r in reactions {
-                                if op_manager.state.should_abort.load(Ordering::SeqCst) { break 'message_loop; }
-                                if r["me"].as_bool().unwrap_or(false) {
-                                    let emoji = r["emoji"]["name"].as_str().unwrap_or("");
-                                    let emoji_id = r["emoji"]["id"].as_str().unwrap_or("");
-                                    let emoji_param = if emoji_id.is_empty() { emoji.to_string() } else { format!("{}:{}", emoji, emoji_id) };
-                                    let react_url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, msg_id, emoji_param);
-                                    let _ = api_handle.send_request(reqwest::Method::DELETE, &react_url, None, &token, is_bearer).await;
-                                }
-                            }
+                let decision = plan::evaluate(&msg, &filters, &content_filter, &time_window, &identity.id, can_manage);
+                let mut acted = false;
+                journal.channels[i].scanned += 1;
+                if decision.matched { journal.channels[i].matched += 1; }
+
+                let mut outcome = None;
+                if filters.simulation {
+                    planned.extend(PlannedAction::from_decision(&channel_id, &msg, &decision, keep_messages));
+                    if decision.delete { outcome = Some(MessageOutcome::Simulated); }
+                } else {
+                    for emoji in &decision.reactions {
+                        if op.is_aborted() {
+                            journal.save(app_handle)?;
+                            return Ok(false);
                         }
+                        let react_url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, msg_id, emoji);
+                        // A cancelled removal returns before the cursor moves, so resuming revisits this message.
+                        let attempt = attempt_delete(&api_handle, &react_url, &token, is_bearer).await?;
+                        journal.channels[i].rate_limited_retries += attempt.rate_limited as u64;
+                        match attempt.outcome {
+                            MessageOutcome::Deleted => journal.channels[i].reactions_removed += 1,
+                            _ => journal.channels[i].record_error(attempt.error(msg_id, "reaction")),
+                        }
+                        acted = true;
                     }
 
-                    if matches_query {
-                        let del_url = format!("https://discord.com/api/v9/channels/{}/messages/{}", channel_id, msg_id);
-                        let del_res = api_handle.send_request(reqwest::Method::DELETE, &del_url, None, &token, is_bearer).await;
-                        if let Ok(res) = del_res { if res.status().is_success() { deleted_total += 1; } }
+                    if decision.delete {
+                        let attempt = preserve_and_delete(&api_handle, op, &token, is_bearer, archive.as_ref(), attachment_store.as_ref(), &channel_id, &msg).await?;
+                        if archive.is_some() { journal.channels[i].archived += 1; }
+                        journal.channels[i].rate_limited_retries += attempt.rate_limited as u64;
+                        if attempt.outcome == MessageOutcome::Failed {
+                            journal.channels[i].record_error(attempt.error(msg_id, "delete"));
+                        }
+                        outcome = Some(attempt.outcome);
+                        acted = true;
                     }
-                } else {
-                    if matches_query { deleted_total += 1; }
+                }
+                if decision.skipped_not_ours {
+                    outcome = Some(MessageOutcome::SkippedNotOurs);
                 }
 
-                if deleted_total % 10 == 0 {
-                    let _ = window.emit("deletion_progress", serde_json::json!({ "current": i + 1, "total": channel_ids.len(), "id": channel_id, "deleted_count": deleted_total, "status": if simulation { "simulating" } else { "purging" } }));
+                match outcome {
+                    Some(MessageOutcome::Deleted | MessageOutcome::Simulated) => {
+                        journal.channels[i].deleted += 1;
+                        journal.deleted_total += 1;
+                    }
+                    Some(MessageOutcome::SkippedNotOurs) => journal.channels[i].skipped += 1,
+                    Some(MessageOutcome::Forbidden) => journal.channels[i].forbidden += 1,
+                    Some(MessageOutcome::AlreadyGone) => journal.channels[i].not_found += 1,
+                    Some(MessageOutcome::Failed) => journal.channels[i].failed += 1,
+                    None => {}
+                }
+
+                journal.channels[i].before = Some(msg_id.to_string());
+                if acted { journal.save(app_handle)?; }
+
+                if outcome.is_some() {
+                    unreported += 1;
+                    if unreported == PROGRESS_EVERY {
+                        emit_purge_progress(window, journal, i);
+                        unreported = 0;
+                    }
                 }
             }
+            if unreported > 0 { emit_purge_progress(window, journal, i); }
+            PurgePlan::append(app_handle, &journal.job_id, &planned)?;
+            journal.save(app_handle)?;
+
+            // Pages are newest-first: once the oldest message predates the window, nothing older can match.
+            if oldest_id.is_some_and(|id| time_window.is_before_window(id)) { break; }
         }
+
+        journal.channels[i].completed = true;
+        journal.save(app_handle)?;
     }
-    op_manager.state.reset();
-    let _ = window.emit("deletion_complete", ());
-    Logger::info(&app_handle, &format!("[OP] Destructive purge complete. Items nullified: {}", deleted_total), None);
-    Ok(())
+    Ok(true)
+}
+
+/// Messages with an outcome handled between two `deletion_progress` events of a purge; the rest of
+/// a page is reported when the page is done.
+const PROGRESS_EVERY: u64 = 25;
+
+/// Reports the running totals of the channel at `i`, up to the message its cursor points at.
+fn emit_purge_progress(window: &impl Emitter<tauri::Wry>, journal: &JobJournal, i: usize) {
+    let channel = &journal.channels[i];
+    let _ = window.emit("deletion_progress", serde_json::json!({
+        "job_id": journal.job_id,
+        "current": i + 1,
+        "total": journal.channels.len(),
+        "id": channel.channel_id,
+        "message_id": channel.before,
+        "deleted_count": journal.deleted_total,
+        "skipped_count": channel.skipped,
+        "forbidden_count": channel.forbidden,
+        "failed_count": channel.failed,
+        "status": if journal.filters.simulation { "simulating" } else { "purging" },
+    }));
+}
+
+/// Result of one DELETE call, shared by history purges, targeted deletes and plan execution.
+pub(crate) struct DeleteAttempt {
+    pub outcome: MessageOutcome,
+    status: Option<u16>,
+    detail: Option<String>,
+    rate_limited: u32,
+}
+
+impl DeleteAttempt {
+    fn error(&self, message_id: &str, action: &str) -> ActionError {
+        ActionError { message_id: message_id.to_string(), action: action.to_string(), status: self.status, detail: self.detail.clone() }
+    }
+}
+
+/// Failures are reported in the attempt. The only error is a cancelled request: the job was
+/// aborted and whether the DELETE landed is unknown, so the message must not be counted as handled.
+pub(crate) async fn attempt_delete(api_handle: &ApiHandle, url: &str, token: &str, is_bearer: bool) -> Result<DeleteAttempt, AppError> {
+    Ok(match api_handle.send_request_counted(reqwest::Method::DELETE, url, None, token, is_bearer).await {
+        Ok((res, rate_limited)) => {
+            let status = res.status();
+            let outcome = match status {
+                s if s.is_success() => MessageOutcome::Deleted,
+                reqwest::StatusCode::FORBIDDEN => MessageOutcome::Forbidden,
+                reqwest::StatusCode::NOT_FOUND => MessageOutcome::AlreadyGone,
+                _ => MessageOutcome::Failed,
+            };
+            let detail = if outcome == MessageOutcome::Failed { res.text().await.ok() } else { None };
+            DeleteAttempt { outcome, status: Some(status.as_u16()), detail, rate_limited }
+        }
+        Err(e) if e.is_cancelled() => return Err(e),
+        Err(e) => DeleteAttempt { outcome: MessageOutcome::Failed, status: None, detail: Some(e.to_string()), rate_limited: 0 },
+    })
+}
+
+/// Saves the message's media and archives it (when enabled), then deletes it.
+/// A media download or archive write failure aborts before the DELETE is sent, so the job stops
+/// with its cursor on this message and a resume retries it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn preserve_and_delete(
+    api_handle: &ApiHandle,
+    op: &OperationState,
+    token: &str,
+    is_bearer: bool,
+    archive: Option<&MessageArchive>,
+    attachment_store: Option<&AttachmentStore>,
+    channel_id: &str,
+    msg: &serde_json::Value,
+) -> Result<DeleteAttempt, AppError> {
+    let msg_id = msg["id"].as_str().unwrap_or_default();
+    // CDN links stop resolving once the message is gone, so media is fetched first.
+    if let Some(store) = attachment_store {
+        let failed = store.archive_message(op, channel_id, msg).await?;
+        if failed > 0 {
+            return Err(AppError {
+                user_message: format!("{} media file(s) of message {} could not be saved; the message was kept.", failed, msg_id),
+                error_code: "media_download_failed".into(),
+                technical_details: Some(format!("channel {}", channel_id)),
+            });
+        }
+    }
+    // Nothing is deleted unless its archive copy is safely on disk.
+    if let Some(archive) = archive {
+        archive.append(channel_id, msg)?;
+    }
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}", channel_id, msg_id);
+    attempt_delete(api_handle, &url, token, is_bearer).await
+}
+
+/// Returns the ids of guilds where our base permissions allow deleting other members' messages.
+async fn fetch_manageable_guilds(api_handle: &ApiHandle, token: &str, is_bearer: bool) -> Result<HashSet<String>, AppError> {
+    const ADMINISTRATOR: u64 = 1 << 3;
+    const MANAGE_MESSAGES: u64 = 1 << 13;
+
+    let response = api_handle.send_request(reqwest::Method::GET, "https://discord.com/api/v9/users/@me/guilds", None, token, is_bearer).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(AppError { user_message: format!("Permission lookup failed (HTTP {})", status), ..Default::default() });
+    }
+    let guilds: Vec<serde_json::Value> = response.json().await?;
+    Ok(guilds.into_iter().filter(|g| {
+        let permissions = g["permissions"].as_str().and_then(|p| p.parse::<u64>().ok()).unwrap_or(0);
+        permissions & (ADMINISTRATOR | MANAGE_MESSAGES) != 0
+    }).filter_map(|g| g["id"].as_str().map(|s| s.to_string())).collect())
+}
+
+/// Opens (or returns the existing) DM channel with `user_id` via the create-DM endpoint.
+async fn open_dm_channel(api_handle: &ApiHandle, token: &str, is_bearer: bool, user_id: &str) -> Result<Channel, AppError> {
+    let response = api_handle.send_request(reqwest::Method::POST, "https://discord.com/api/v9/users/@me/channels", Some(serde_json::json!({ "recipient_id": user_id })), token, is_bearer).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError { user_message: format!("Failed to reopen DM with {} (HTTP {})", user_id, status), error_code: "dm_open_failed".into(), technical_details: Some(body) });
+    }
+    let ch: serde_json::Value = response.json().await?;
+    let name = ch["recipients"].as_array().and_then(|r| r.first()).and_then(|u| u["username"].as_str()).map(|s| format!("DM with {}", s));
+    Ok(Channel { id: ch["id"].as_str().unwrap_or_default().to_string(), name, channel_type: ch["type"].as_u64().unwrap_or(1) as u8, parent_id: None, parent_name: None })
+}
+
+/// Closes DM channels. Closing only hides a DM from the list; its history is untouched.
+async fn close_dms(app_handle: &AppHandle, channel_ids: &[String]) {
+    let Ok((token, is_bearer)) = session::active_token(app_handle).await else { return; };
+    let api_handle = app_handle.state::<ApiHandle>();
+    for channel_id in channel_ids {
+        let url = format!("https://discord.com/api/v9/channels/{}", channel_id);
+        if !api_handle.send_request(reqwest::Method::DELETE, &url, None, &token, is_bearer).await.is_ok_and(|r| r.status().is_success()) {
+            Logger::warn(app_handle, &format!("[OP] Failed to close reopened DM {}", channel_id), None);
+        }
+    }
+    Logger::debug(app_handle, &format!("[OP] Closed {} reopened DM(s)", channel_ids.len()), None);
+}
+
+#[tauri::command]
+pub async fn open_dm(app_handle: AppHandle, user_id: String) -> Result<Channel, AppError> {
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
+    if is_bearer { return Err(AppError { user_message: "DMs restricted in Official Gate.".into(), ..Default::default() }); }
+    let api_handle = app_handle.state::<ApiHandle>();
+    open_dm_channel(&api_handle, &token, is_bearer, &user_id).await
+}
+
+/// Reopens the DMs with `user_ids` (or with every friend when `from_relationships` is set) and purges them.
+/// With `close_after`, DMs that were not open beforehand are closed again once the job completes.
+#[tauri::command]
+pub async fn purge_dms_with_users(
+    app_handle: AppHandle,
+    window: tauri::Window,
+    user_ids: Vec<String>,
+    from_relationships: bool,
+    close_after: bool,
+    filters: PurgeFilters,
+) -> Result<(), AppError> {
+    filters.compile_content_filter()?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
+    if is_bearer { return Err(AppError { user_message: "DMs restricted in Official Gate.".into(), ..Default::default() }); }
+    let api_handle = app_handle.state::<ApiHandle>();
+
+    let mut user_ids = user_ids;
+    if from_relationships {
+        for rel in fetch_relationships(app_handle.clone()).await? {
+            // Type 1 is an accepted friend.
+            if rel.rel_type == 1 && !user_ids.contains(&rel.id) {
+                user_ids.push(rel.id);
+            }
+        }
+    }
+
+    // Only DMs that were closed before the job may be closed after it, so without the open set
+    // there is no safe way to honour `close_after`.
+    let already_open: HashSet<String> = if close_after {
+        let response = api_handle.send_request(reqwest::Method::GET, "https://discord.com/api/v9/users/@me/channels", None, &token, is_bearer).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(AppError { user_message: format!("Failed to read open DMs (HTTP {})", status), error_code: "dm_list_failed".into(), technical_details: response.text().await.ok() });
+        }
+        response.json::<Vec<serde_json::Value>>().await?.iter().filter_map(|c| c["id"].as_str().map(|s| s.to_string())).collect()
+    } else {
+        HashSet::new()
+    };
+
+    let mut channel_ids = Vec::new();
+    let mut reopened = Vec::new();
+    for user_id in &user_ids {
+        match open_dm_channel(&api_handle, &token, is_bearer, user_id).await {
+            Ok(channel) => {
+                if !already_open.contains(&channel.id) { reopened.push(channel.id.clone()); }
+                channel_ids.push(channel.id);
+            }
+            Err(e) => Logger::warn(&app_handle, &format!("[OP] Skipping user {}: {}", user_id, e.user_message), None),
+        }
+    }
+    if channel_ids.is_empty() {
+        return Err(AppError { user_message: "No DM could be reopened.".into(), error_code: "dm_open_failed".into(), ..Default::default() });
+    }
+
+    let mut journal = JobJournal::new(channel_ids, filters);
+    if close_after { journal.reopened_dms = reopened; }
+    Logger::info(&app_handle, &format!("[OP] DM purge initialized for {} nodes (Sim: {}, Job: {})", journal.channels.len(), journal.filters.simulation, journal.job_id), None);
+    let result = run_purge_job(&app_handle, &window, &mut journal).await;
+    // A rejected start writes no journal, so nothing would close the DMs reopened for it later.
+    if result.as_ref().is_err_and(|e| e.error_code == "operation_already_running") {
+        close_dms(&app_handle, &journal.reopened_dms).await;
+    }
+    result
 }
 
 #[tauri::command]
 pub async fn fetch_preview_messages(app_handle: AppHandle, channel_id: String) -> Result<Vec<serde_json::Value>, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let api_handle = app_handle.state::<ApiHandle>();
     let response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/channels/{}/messages?limit=5", channel_id), None, &token, is_bearer).await?;
     if !response.status().is_success() { return Err(AppError { user_message: "Preview failed.".into(), ..Default::default() }); }
@@ -262,75 +815,73 @@ pub async fn fetch_preview_messages(app_handle: AppHandle, channel_id: String) -
 
 #[tauri::command]
 pub async fn stealth_privacy_wipe(app_handle: AppHandle) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     if is_bearer { return Err(AppError { user_message: "Stealth Mode restricted in Official Gate.".into(), ..Default::default() }); }
 
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
+    let op = op_manager.begin(&app_handle, "privacy_wipe", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     Logger::info(&app_handle, "[STEALTH] Privacy protocol execution loop active...", None);
 
     // 1. Wipe Custom Status
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[STEALTH] Nullifying custom status", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me/settings", Some(serde_json::json!({ "custom_status": null })), &token, is_bearer).await;
     }
 
     // 2. Global DM Disable
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[STEALTH] Updating DM buffer protocols", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me/settings", Some(serde_json::json!({ "default_guilds_restricted": true })), &token, is_bearer).await;
     }
 
     // 3. Presence Privacy
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[STEALTH] Masking presence game/activity data", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me/settings", Some(serde_json::json!({ "show_current_game": false, "restricted_guilds": [] })), &token, is_bearer).await;
     }
 
-    op_manager.state.reset();
+    drop(op);
     Logger::info(&app_handle, "[STEALTH] Privacy protocol sequence complete.", None);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn bury_audit_log(app_handle: AppHandle, window: tauri::Window, guild_id: String, channel_id: String) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
-    let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
-
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     if is_bearer { return Err(AppError { user_message: "Audit Log Burial restricted in Official Gate.".into(), ..Default::default() }); }
+    let op_manager = app_handle.state::<OperationManager>();
+    let op = op_manager.begin(&app_handle, "bury_audit_log", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     Logger::info(&app_handle, &format!("[AUDIT] Starting burial sequence in guild {}", guild_id), None);
 
     let original_channel_response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/channels/{}", channel_id), None, &token, is_bearer).await?;
     if !original_channel_response.status().is_success() {
-        op_manager.state.reset();
         return Err(AppError { user_message: "Failed to resolve target node.".into(), ..Default::default() });
     }
     let original_channel_name = original_channel_response.json::<serde_json::Value>().await?["name"].as_str().unwrap_or("general").to_string();
 
     for i in 0..10 {
-        op_manager.state.wait_if_paused().await;
-        if op_manager.state.should_abort.load(Ordering::SeqCst) { break; }
+        op.wait_if_paused().await;
+        if op.is_aborted() { break; }
 
         let new_name = format!("{}-temp-{}", original_channel_name, i);
         Logger::debug(&app_handle, &format!("[AUDIT] Phase {}: cyclic node rename", i), None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, &format!("https://discord.com/api/v9/channels/{}", channel_id), Some(serde_json::json!({ "name": new_name })), &token, is_bearer).await;
         
-        let _ = window.emit("audit_log_progress", serde_json::json!({ "current": i + 1, "total": 20, "status": format!("Burying node data phase {}", i) }));
+        let _ = window.emit("audit_log_progress", serde_json::json!({ "job_id": op.job_id, "current": i + 1, "total": 20, "status": format!("Burying node data phase {}", i) }));
         tokio::time::sleep(Duration::from_millis(500)).await;
 
         let _ = api_handle.send_request(reqwest::Method::PATCH, &format!("https://discord.com/api/v9/channels/{}", channel_id), Some(serde_json::json!({ "name": original_channel_name })), &token, is_bearer).await;
         tokio::time::sleep(Duration::from_millis(500)).await;
     }
 
-    op_manager.state.reset();
+    drop(op);
     let _ = window.emit("audit_log_complete", ());
     Logger::info(&app_handle, "[AUDIT] Burial protocol finalized.", None);
     Ok(())
@@ -338,26 +889,24 @@ pub async fn bury_audit_log(app_handle: AppHandle, window: tauri::Window, guild_
 
 #[tauri::command]
 pub async fn webhook_ghosting(app_handle: AppHandle, window: tauri::Window, guild_id: String) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
+    if is_bearer { return Err(AppError { user_message: "Webhook Ghosting restricted in Official Gate.".into(), ..Default::default() }); }
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
-
-    if is_bearer { op_manager.state.reset(); return Err(AppError { user_message: "Webhook Ghosting restricted in Official Gate.".into(), ..Default::default() }); }
+    let op = op_manager.begin(&app_handle, "webhook_ghosting", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     Logger::info(&app_handle, &format!("[WEBHOOK] Ghosting identity hooks in node {}", guild_id), None);
 
     let webhooks_response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/guilds/{}/webhooks", guild_id), None, &token, is_bearer).await?;
     if !webhooks_response.status().is_success() {
-        op_manager.state.reset();
         return Err(AppError { user_message: "Failed to scan webhooks.".into(), ..Default::default() });
     }
     let webhooks: Vec<serde_json::Value> = webhooks_response.json().await?;
 
     let mut deleted_webhooks = 0;
     for webhook in &webhooks {
-        op_manager.state.wait_if_paused().await;
-        if op_manager.state.should_abort.load(Ordering::SeqCst) { break; }
+        op.wait_if_paused().await;
+        if op.is_aborted() { break; }
 
         let webhook_id = webhook["id"].as_str().unwrap_or_default();
         let webhook_creator_id = webhook["user"]["id"].as_str().unwrap_or_default();
@@ -368,10 +917,10 @@ pub async fn webhook_ghosting(app_handle: AppHandle, window: tauri::Window, guil
             let _ = api_handle.send_request(reqwest::Method::DELETE, &format!("https://discord.com/api/v9/webhooks/{}", webhook_id), None, &token, is_bearer).await;
             deleted_webhooks += 1;
         }
-        let _ = window.emit("webhook_progress", serde_json::json!({ "current": deleted_webhooks, "total": webhooks.len(), "status": "Ghosting active" }));
+        let _ = window.emit("webhook_progress", serde_json::json!({ "job_id": op.job_id, "current": deleted_webhooks, "total": webhooks.len(), "status": "Ghosting active" }));
     }
 
-    op_manager.state.reset();
+    drop(op);
     let _ = window.emit("webhook_complete", ());
     Logger::info(&app_handle, &format!("[WEBHOOK] Ghosting complete. Nullified {} identity hooks", deleted_webhooks), None);
     Ok(())
@@ -379,72 +928,118 @@ pub async fn webhook_ghosting(app_handle: AppHandle, window: tauri::Window, guil
 
 #[tauri::command]
 pub async fn nitro_stealth_wipe(app_handle: AppHandle) -> Result<(), AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
-    let api_handle = app_handle.state::<ApiHandle>();
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     if is_bearer { return Err(AppError { user_message: "Nitro Stealth restricted in Official Gate.".into(), ..Default::default() }); }
 
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_running.store(true, Ordering::SeqCst);
+    let op = op_manager.begin(&app_handle, "nitro_wipe", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
 
     Logger::info(&app_handle, "[NITRO] Initiating stealth wipe protocol for premium metadata", None);
 
     // 1. Clear About Me
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[NITRO] Nullifying bio/about-me", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me", Some(serde_json::json!({ "bio": "" })), &token, is_bearer).await;
     }
 
     // 2. Clear Pronouns
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[NITRO] Nullifying profile pronouns", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me/settings", Some(serde_json::json!({ "pronouns": "" })), &token, is_bearer).await;
     }
 
     // 3. Reset Banner
-    op_manager.state.wait_if_paused().await;
-    if !op_manager.state.should_abort.load(Ordering::SeqCst) {
+    op.wait_if_paused().await;
+    if !op.is_aborted() {
         Logger::debug(&app_handle, "[NITRO] Nullifying profile banner", None);
         let _ = api_handle.send_request(reqwest::Method::PATCH, "https://discord.com/api/v9/users/@me", Some(serde_json::json!({ "banner": null })), &token, is_bearer).await;
     }
 
-    op_manager.state.reset();
+    drop(op);
     Logger::info(&app_handle, "[NITRO] Stealth wipe sequence complete.", None);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn pause_operation(app_handle: AppHandle) -> Result<(), AppError> {
+pub async fn pause_operation(app_handle: AppHandle, job_id: String) -> Result<(), AppError> {
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_paused.store(true, Ordering::SeqCst);
-    Logger::warn(&app_handle, "[OP] Execution loop PAUSED", None);
+    op_manager.get(&job_id)?.pause();
+    Logger::warn(&app_handle, &format!("[OP] Execution loop PAUSED (Job: {})", job_id), None);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn resume_operation(app_handle: AppHandle) -> Result<(), AppError> {
+pub async fn resume_operation(app_handle: AppHandle, job_id: String) -> Result<(), AppError> {
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.is_paused.store(false, Ordering::SeqCst);
-    Logger::info(&app_handle, "[OP] Execution loop RESUMED", None);
+    op_manager.get(&job_id)?.resume();
+    Logger::info(&app_handle, &format!("[OP] Execution loop RESUMED (Job: {})", job_id), None);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn abort_operation(app_handle: AppHandle) -> Result<(), AppError> {
+pub async fn abort_operation(app_handle: AppHandle, job_id: String) -> Result<(), AppError> {
     let op_manager = app_handle.state::<OperationManager>();
-    op_manager.state.should_abort.store(true, Ordering::SeqCst);
-    op_manager.state.is_paused.store(false, Ordering::SeqCst); 
-    Logger::error(&app_handle, "[OP] ABORT command received. Terminating loops...", None);
+    op_manager.get(&job_id)?.abort();
+    Logger::error(&app_handle, &format!("[OP] ABORT command received. Terminating loops... (Job: {})", job_id), None);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_operation_status(app_handle: AppHandle) -> Result<OperationStatus, AppError> {
+pub async fn get_operation_status(app_handle: AppHandle, job_id: String) -> Result<OperationStatus, AppError> {
     let op_manager = app_handle.state::<OperationManager>();
-    Ok(OperationStatus {
-        is_running: op_manager.state.is_running.load(Ordering::SeqCst),
-        is_paused: op_manager.state.is_paused.load(Ordering::SeqCst),
-        should_abort: op_manager.state.should_abort.load(Ordering::SeqCst),
-    })
+    Ok(op_manager.get(&job_id)?.status())
+}
+
+/// Every operation currently registered, oldest first.
+#[tauri::command]
+pub async fn list_jobs(app_handle: AppHandle) -> Result<Vec<OperationStatus>, AppError> {
+    Ok(app_handle.state::<OperationManager>().list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::mpsc;
+    use crate::core::op_manager::OperationManager;
+
+    /// Answers every request with HTTP 500.
+    async fn failing_cdn() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buffer).await.unwrap_or(0);
+                    if n == 0 { break; }
+                    request.extend_from_slice(&buffer[..n]);
+                }
+                let _ = socket.write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{}/attachments/1/2/file.png?ex=1", addr)
+    }
+
+    #[tokio::test]
+    async fn messages_whose_media_cannot_be_saved_are_not_deleted() {
+        let url = failing_cdn().await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::open_at(dir.path().to_path_buf(), None).unwrap();
+        let manager = OperationManager::new();
+        let op = manager.claim("purge", None, true).unwrap();
+        // Nothing drains the inbox: any DELETE would show up in it.
+        let (tx, mut inbox) = mpsc::channel(1);
+        let api_handle = ApiHandle::new(tx);
+        let message = serde_json::json!({ "id": "9", "attachments": [{ "url": url }] });
+
+        let err = preserve_and_delete(&api_handle, &op, "token", false, None, Some(&store), "1", &message).await.err().unwrap();
+        assert_eq!(err.error_code, "media_download_failed");
+        assert!(inbox.try_recv().is_err());
+    }
 }
@@ -0,0 +1,385 @@
+// src-tauri/src/api/export.rs
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Emitter};
+use crate::api::rate_limiter::ApiHandle;
+use crate::auth::session;
+use crate::core::error::AppError;
+use crate::core::logger::Logger;
+use crate::core::op_manager::{OperationManager, OperationState};
+use crate::core::snowflake::{self, SnowflakeWindow};
+
+/// Files written by `export_channel_history`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportResult {
+    pub channel_id: String,
+    pub channel_name: String,
+    pub directory: String,
+    pub message_count: usize,
+    pub files: Vec<String>,
+}
+
+/// Display names resolved while walking a channel, used to render mentions and headers.
+#[derive(Default)]
+struct NameResolver {
+    users: HashMap<String, String>,
+    channels: HashMap<String, String>,
+    roles: HashMap<String, String>,
+}
+
+impl NameResolver {
+    fn display_name(user: &serde_json::Value) -> String {
+        user["global_name"].as_str().or_else(|| user["username"].as_str()).unwrap_or("Unknown User").to_string()
+    }
+
+    fn learn(&mut self, message: &serde_json::Value) {
+        let users = std::iter::once(&message["author"]).chain(message["mentions"].as_array().into_iter().flatten());
+        for user in users {
+            if let Some(id) = user["id"].as_str() {
+                self.users.entry(id.to_string()).or_insert_with(|| Self::display_name(user));
+            }
+        }
+    }
+
+    /// Replaces `<@id>` / `<@!id>` user, `<@&id>` role and `<#id>` channel mentions with `@name`
+    /// or `#name`, keeping the id when the name is unknown.
+    fn render(&self, content: &str) -> String {
+        let mut out = String::with_capacity(content.len());
+        let mut rest = content;
+        while let Some(start) = rest.find('<') {
+            out.push_str(&rest[..start]);
+            let tail = &rest[start + 1..];
+            let mention = if let Some(id_part) = tail.strip_prefix("@&") {
+                Some(('@', &self.roles, id_part))
+            } else if let Some(id_part) = tail.strip_prefix("@!").or_else(|| tail.strip_prefix('@')) {
+                Some(('@', &self.users, id_part))
+            } else {
+                tail.strip_prefix('#').map(|id_part| ('#', &self.channels, id_part))
+            };
+            match mention.and_then(|(sigil, names, id_part)| Some((sigil, names, id_part, id_part.find('>')?))) {
+                Some((sigil, names, id_part, end)) if end > 0 && id_part[..end].bytes().all(|b| b.is_ascii_digit()) => {
+                    let id = &id_part[..end];
+                    let _ = write!(out, "{}{}", sigil, names.get(id).map_or(id, String::as_str));
+                    rest = &id_part[end + 1..];
+                }
+                _ => {
+                    out.push('<');
+                    rest = tail;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Pages spooled to disk while a channel is walked newest to oldest, so the export files can be
+/// written oldest first without holding the whole history in memory. Removed when dropped.
+struct PageSpool {
+    dir: PathBuf,
+    pages: usize,
+}
+
+impl PageSpool {
+    fn create(dir: PathBuf) -> Result<Self, AppError> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, pages: 0 })
+    }
+
+    fn page_path(&self, page: usize) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", page))
+    }
+
+    /// Stores one page as received from the API (newest first), reversed to oldest first.
+    fn push(&mut self, messages: &[serde_json::Value]) -> Result<(), AppError> {
+        if messages.is_empty() { return Ok(()); }
+        let mut out = Vec::new();
+        for message in messages.iter().rev() {
+            serde_json::to_writer(&mut out, message)?;
+            out.push(b'\n');
+        }
+        std::fs::write(self.page_path(self.pages), out)?;
+        self.pages += 1;
+        Ok(())
+    }
+
+    /// Visits every spooled message oldest first, with its JSON line.
+    fn for_each(&self, mut visit: impl FnMut(&str, &serde_json::Value) -> Result<(), AppError>) -> Result<(), AppError> {
+        for page in (0..self.pages).rev() {
+            let text = std::fs::read_to_string(self.page_path(page))?;
+            for line in text.lines() {
+                visit(line, &serde_json::from_str(line)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PageSpool {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Walks a channel or DM from newest to oldest (the same paging `bulk_delete_messages` uses) and writes
+/// `transcript.html`, `messages.jsonl` and `summary.csv` to `<app_local_data_dir>/exports/<channel_id>-<timestamp>/`.
+///
+/// # Logic
+/// Pages are spooled to disk as they arrive. Once every name is known, the spool is read back
+/// oldest first and all three files are streamed from it, one page in memory at a time.
+#[tauri::command]
+pub async fn export_channel_history(
+    app_handle: AppHandle,
+    window: tauri::Window,
+    channel_id: String,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+) -> Result<ExportResult, AppError> {
+    let op_manager = app_handle.state::<OperationManager>();
+    let op = op_manager.begin(&app_handle, "export", None, false)?;
+    // A request cut short by the abort surfaces the same way as an abort between pages.
+    export_channel(&app_handle, &window, &op, &channel_id, SnowflakeWindow::new(start_time, end_time)).await
+        .map_err(|e| if e.is_cancelled() { export_aborted() } else { e })
+}
+
+fn export_aborted() -> AppError {
+    AppError { user_message: "Export aborted.".into(), error_code: "aborted".into(), ..Default::default() }
+}
+
+async fn export_channel(app_handle: &AppHandle, window: &tauri::Window, op: &OperationState, channel_id: &str, time_window: SnowflakeWindow) -> Result<ExportResult, AppError> {
+    if channel_id.is_empty() || !channel_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AppError { user_message: "Invalid channel identifier.".into(), error_code: "invalid_channel_id".into(), ..Default::default() });
+    }
+    let (token, is_bearer) = session::active_token(app_handle).await?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
+
+    let mut resolver = NameResolver::default();
+    let channel_name = resolve_channel(&api_handle, &token, is_bearer, channel_id, &mut resolver).await?;
+    Logger::info(app_handle, &format!("[EXPORT] Capturing history of {}", channel_name), None);
+
+    let exports = app_handle.path().app_local_data_dir()?.join("exports");
+    let mut spool = PageSpool::create(exports.join(format!(".spool-{}", op.job_id)))?;
+    let mut message_count = 0;
+    let mut before = time_window.max_id.map(|id| id.to_string());
+    loop {
+        op.wait_if_paused().await;
+        if op.is_aborted() {
+            return Err(export_aborted());
+        }
+
+        let mut url = format!("https://discord.com/api/v9/channels/{}/messages?limit=100", channel_id);
+        if let Some(before) = &before { url.push_str(&format!("&before={}", before)); }
+        let response = api_handle.send_request(reqwest::Method::GET, &url, None, &token, is_bearer).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(AppError { user_message: format!("History fetch failed (HTTP {})", status), technical_details: response.text().await.ok(), ..Default::default() });
+        }
+        let page: Vec<serde_json::Value> = response.json().await?;
+        let Some(oldest) = page.last().and_then(|m| m["id"].as_str()).map(|s| s.to_string()) else { break; };
+
+        let in_window: Vec<serde_json::Value> = page.into_iter()
+            .filter(|m| m["id"].as_str().and_then(snowflake::parse).is_some_and(|id| time_window.contains(id)))
+            .collect();
+        in_window.iter().for_each(|m| resolver.learn(m));
+        spool.push(&in_window)?;
+        message_count += in_window.len();
+        let _ = window.emit("export_progress", serde_json::json!({ "job_id": op.job_id, "channel_id": channel_id, "fetched": message_count }));
+
+        if snowflake::parse(&oldest).is_some_and(|id| time_window.is_before_window(id)) { break; }
+        before = Some(oldest);
+    }
+
+    let dir = export_dir(&exports, channel_id)?;
+    let files = write_export(&dir, &spool, &channel_name, message_count, &resolver)?;
+    Logger::info(app_handle, &format!("[EXPORT] {} messages written to {}", message_count, dir.display()), None);
+
+    Ok(ExportResult {
+        channel_id: channel_id.to_string(),
+        channel_name,
+        directory: dir.to_string_lossy().into_owned(),
+        message_count,
+        files,
+    })
+}
+
+/// Streams the spooled messages into `messages.jsonl`, `summary.csv` and `transcript.html`.
+fn write_export(dir: &Path, spool: &PageSpool, channel_name: &str, message_count: usize, resolver: &NameResolver) -> Result<Vec<String>, AppError> {
+    let paths = ["messages.jsonl", "summary.csv", "transcript.html"].map(|name| dir.join(name));
+    let create = |path: &PathBuf| -> Result<BufWriter<std::fs::File>, AppError> { Ok(BufWriter::new(std::fs::File::create(path)?)) };
+    let (mut jsonl, mut csv, mut html) = (create(&paths[0])?, create(&paths[1])?, create(&paths[2])?);
+    csv.write_all(CSV_HEADER.as_bytes())?;
+    html.write_all(html_header(channel_name, message_count).as_bytes())?;
+    spool.for_each(|line, message| {
+        jsonl.write_all(line.as_bytes())?;
+        jsonl.write_all(b"\n")?;
+        csv.write_all(csv_row(message, resolver).as_bytes())?;
+        html.write_all(html_message(message, resolver).as_bytes())?;
+        Ok(())
+    })?;
+    html.write_all(HTML_FOOTER.as_bytes())?;
+    for writer in [&mut jsonl, &mut csv, &mut html] {
+        writer.flush()?;
+    }
+    Ok(paths.iter().map(|p| p.to_string_lossy().into_owned()).collect())
+}
+
+/// Resolves the display name of the exported channel. In a guild, the guild's channel and role
+/// names are loaded too so mentions can be rendered; those lookups are best effort.
+async fn resolve_channel(api_handle: &ApiHandle, token: &str, is_bearer: bool, channel_id: &str, resolver: &mut NameResolver) -> Result<String, AppError> {
+    let response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/channels/{}", channel_id), None, token, is_bearer).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(AppError { user_message: format!("Failed to resolve node {} (HTTP {})", channel_id, status), error_code: "channel_lookup_failed".into(), ..Default::default() });
+    }
+    let channel: serde_json::Value = response.json().await?;
+    if let Some(guild_id) = channel["guild_id"].as_str() {
+        for (kind, names) in [("channels", &mut resolver.channels), ("roles", &mut resolver.roles)] {
+            let url = format!("https://discord.com/api/v9/guilds/{}/{}", guild_id, kind);
+            let response = api_handle.send_request(reqwest::Method::GET, &url, None, token, is_bearer).await?;
+            if !response.status().is_success() { continue; }
+            let items: Vec<serde_json::Value> = response.json().await?;
+            names.extend(items.iter().filter_map(|item| Some((item["id"].as_str()?.to_string(), item["name"].as_str()?.to_string()))));
+        }
+    }
+    if let Some(name) = channel["name"].as_str().filter(|n| !n.is_empty()) {
+        return Ok(match channel["guild_id"].as_str() {
+            Some(_) => format!("#{}", name),
+            None => name.to_string(),
+        });
+    }
+    let recipients: Vec<String> = channel["recipients"].as_array().into_iter().flatten().map(NameResolver::display_name).collect();
+    Ok(if recipients.is_empty() { format!("Channel {}", channel_id) } else { format!("DM with {}", recipients.join(", ")) })
+}
+
+fn export_dir(exports: &Path, channel_id: &str) -> Result<PathBuf, AppError> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let dir = exports.join(format!("{}-{}", channel_id, stamp));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub(crate) fn write_file(dir: &Path, name: &str, contents: impl AsRef<[u8]>) -> Result<String, AppError> {
+    let path = dir.join(name);
+    std::fs::write(&path, contents)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+fn attachment_urls(message: &serde_json::Value) -> Vec<&str> {
+    message["attachments"].as_array().into_iter().flatten().filter_map(|a| a["url"].as_str()).collect()
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+const CSV_HEADER: &str = "id,timestamp,author_id,author,content,attachments,reply_to\n";
+
+fn csv_row(m: &serde_json::Value, resolver: &NameResolver) -> String {
+    let row = [
+        m["id"].as_str().unwrap_or_default().to_string(),
+        m["timestamp"].as_str().unwrap_or_default().to_string(),
+        m["author"]["id"].as_str().unwrap_or_default().to_string(),
+        NameResolver::display_name(&m["author"]),
+        resolver.render(m["content"].as_str().unwrap_or_default()),
+        attachment_urls(m).join(" "),
+        m["message_reference"]["message_id"].as_str().unwrap_or_default().to_string(),
+    ];
+    let mut out = row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+    out.push('\n');
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+const HTML_FOOTER: &str = "</body></html>\n";
+
+fn html_header(channel_name: &str, message_count: usize) -> String {
+    let title = escape_html(channel_name);
+    let mut out = String::new();
+    let _ = write!(out, "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>\
+body{{font-family:sans-serif;background:#0a0a0a;color:#e5e5e5;max-width:900px;margin:auto;padding:24px}}\
+.msg{{padding:8px 0;border-bottom:1px solid #222}}.meta{{color:#888;font-size:12px}}.author{{font-weight:bold;color:#fff}}\
+.content{{white-space:pre-wrap;margin-top:4px}}.reply,.embed{{color:#aaa;font-size:13px;border-left:3px solid #444;padding-left:8px;margin:4px 0}}\
+a{{color:#7aa2f7}}</style></head><body>\n<h1>{title}</h1>\n<p class=\"meta\">{} messages, exported {}</p>\n",
+        message_count, chrono::Utc::now().to_rfc3339());
+    out
+}
+
+fn html_message(m: &serde_json::Value, resolver: &NameResolver) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "<div class=\"msg\" id=\"m{}\"><div class=\"meta\"><span class=\"author\">{}</span> &middot; {}</div>",
+        escape_html(m["id"].as_str().unwrap_or_default()),
+        escape_html(&NameResolver::display_name(&m["author"])),
+        escape_html(m["timestamp"].as_str().unwrap_or_default()));
+    if let Some(reply_id) = m["message_reference"]["message_id"].as_str() {
+        let _ = write!(out, "<div class=\"reply\">&#8618; reply to <a href=\"#m{0}\">{0}</a></div>", escape_html(reply_id));
+    }
+    let content = m["content"].as_str().unwrap_or_default();
+    if !content.is_empty() {
+        let _ = write!(out, "<div class=\"content\">{}</div>", escape_html(&resolver.render(content)));
+    }
+    for embed in m["embeds"].as_array().into_iter().flatten() {
+        let heading = embed["title"].as_str().or_else(|| embed["url"].as_str()).unwrap_or("embed");
+        let _ = write!(out, "<div class=\"embed\">{}</div>", escape_html(heading));
+    }
+    for url in attachment_urls(m) {
+        let _ = write!(out, "<div class=\"embed\">&#128206; <a href=\"{0}\">{0}</a></div>", escape_html(url));
+    }
+    out.push_str("</div>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_and_csv_fields_are_rendered_safely() {
+        let mut resolver = NameResolver {
+            channels: HashMap::from([("4".to_string(), "general".to_string())]),
+            roles: HashMap::from([("3".to_string(), "mods".to_string())]),
+            ..Default::default()
+        };
+        resolver.learn(&serde_json::json!({
+            "author": { "id": "1", "username": "nelly" },
+            "mentions": [{ "id": "2", "username": "bob", "global_name": "Bob" }],
+        }));
+        assert_eq!(resolver.render("hi <@2> and <@!1>, ping <@&3> in <#4>, not <@x or <:emoji:5>"), "hi @Bob and @nelly, ping @mods in #general, not <@x or <:emoji:5>");
+        assert_eq!(resolver.render("<@99> <@&98> <#97> <#>"), "@99 @98 #97 <#>");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, \"b\"\nc"), "\"a, \"\"b\"\"\nc\"");
+        assert_eq!(escape_html("<b>&'\""), "&lt;b&gt;&amp;&#39;&quot;");
+    }
+
+    #[test]
+    fn spooled_pages_are_replayed_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool_dir = dir.path().join(".spool");
+        let mut spool = PageSpool::create(spool_dir.clone()).unwrap();
+        let page = |ids: &[u64]| ids.iter().map(|id| serde_json::json!({ "id": id.to_string() })).collect::<Vec<_>>();
+        spool.push(&page(&[6, 5, 4])).unwrap();
+        spool.push(&page(&[])).unwrap();
+        spool.push(&page(&[3, 2, 1])).unwrap();
+
+        let mut ids = Vec::new();
+        spool.for_each(|line, message| {
+            assert_eq!(serde_json::from_str::<serde_json::Value>(line).unwrap(), *message);
+            ids.push(message["id"].as_str().unwrap().to_string());
+            Ok(())
+        }).unwrap();
+        assert_eq!(ids, ["1", "2", "3", "4", "5", "6"]);
+
+        drop(spool);
+        assert!(!spool_dir.exists());
+    }
+}
@@ -0,0 +1,61 @@
+// src-tauri/src/api/package.rs
+
+use tauri::AppHandle;
+use crate::api::discord::run_purge_job;
+use crate::core::data_package::{PackageIndex, PackageMessage, PackageSummary};
+use crate::core::error::AppError;
+use crate::core::journal::{JobJournal, PurgeFilters};
+use crate::core::logger::Logger;
+
+/// Parses a "Request My Data" `package.zip` and replaces the stored package index.
+#[tauri::command]
+pub async fn import_data_package(app_handle: AppHandle, path: String) -> Result<PackageSummary, AppError> {
+    Logger::info(&app_handle, "[PACKAGE] Indexing data package...", None);
+    let index = tauri::async_runtime::spawn_blocking(move || PackageIndex::from_zip(std::path::Path::new(&path))).await??;
+    index.save(&app_handle)?;
+    let summary = index.summary();
+    Logger::info(&app_handle, &format!("[PACKAGE] Indexed {} messages across {} nodes in {} guilds", summary.message_count, summary.channel_count, summary.guild_count), None);
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn get_package_summary(app_handle: AppHandle) -> Result<PackageSummary, AppError> {
+    Ok(PackageIndex::load(&app_handle)?.summary())
+}
+
+/// Message ids (newest first) the package lists for one channel.
+#[tauri::command]
+pub async fn get_package_messages(app_handle: AppHandle, channel_id: String) -> Result<Vec<PackageMessage>, AppError> {
+    let index = PackageIndex::load(&app_handle)?;
+    let channel = index.channel(&channel_id).ok_or_else(|| AppError {
+        user_message: "Channel not present in the data package.".into(),
+        error_code: "package_channel_not_found".into(),
+        ..Default::default()
+    })?;
+    Ok(channel.messages.clone())
+}
+
+/// Runs a regular purge job over channels taken from the package index (every indexed channel
+/// when `channel_ids` is omitted), including ones `fetch_channels` can no longer list.
+#[tauri::command]
+pub async fn purge_package_channels(
+    app_handle: AppHandle,
+    window: tauri::Window,
+    channel_ids: Option<Vec<String>>,
+    filters: PurgeFilters,
+) -> Result<(), AppError> {
+    filters.compile_content_filter()?;
+    let index = PackageIndex::load(&app_handle)?;
+    let channel_ids: Vec<String> = index.channels.iter()
+        .filter(|c| !c.messages.is_empty())
+        .filter(|c| channel_ids.as_ref().is_none_or(|ids| ids.contains(&c.channel_id)))
+        .map(|c| c.channel_id.clone())
+        .collect();
+    if channel_ids.is_empty() {
+        return Err(AppError { user_message: "No matching channels in the data package.".into(), error_code: "package_channel_not_found".into(), ..Default::default() });
+    }
+
+    let mut journal = JobJournal::new(channel_ids, filters);
+    Logger::info(&app_handle, &format!("[OP] Package purge initialized for {} nodes (Sim: {}, Job: {})", journal.channels.len(), journal.filters.simulation, journal.job_id), None);
+    run_purge_job(&app_handle, &window, &mut journal).await
+}
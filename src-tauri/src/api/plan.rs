@@ -0,0 +1,156 @@
+// src-tauri/src/api/plan.rs
+
+use tauri::{AppHandle, Manager, Emitter};
+use crate::api::discord::{attempt_delete, preserve_and_delete, MessageOutcome, TargetedDeleteReport};
+use crate::api::export::{csv_field, write_file};
+use crate::api::rate_limiter::ApiHandle;
+use crate::auth::session;
+use crate::core::archive::MessageArchive;
+use crate::core::attachments::AttachmentStore;
+use crate::core::error::AppError;
+use crate::core::journal::{JobJournal, JobStatus};
+use crate::core::logger::Logger;
+use crate::core::op_manager::OperationManager;
+use crate::core::plan::{PlannedKind, PurgePlan};
+
+/// Loads the plan recorded by a simulation job, together with the filters it was built from.
+fn load_plan(app_handle: &AppHandle, plan_id: &str) -> Result<(PurgePlan, JobStatus), AppError> {
+    let journal = JobJournal::load(app_handle, plan_id)?;
+    if !journal.filters.simulation {
+        return Err(AppError { user_message: "This job was not a simulation and has no plan.".into(), error_code: "plan_not_found".into(), ..Default::default() });
+    }
+    Ok((PurgePlan::load(app_handle, plan_id, journal.filters)?, journal.status))
+}
+
+#[tauri::command]
+pub async fn get_plan(app_handle: AppHandle, plan_id: String) -> Result<PurgePlan, AppError> {
+    Ok(load_plan(&app_handle, &plan_id)?.0)
+}
+
+/// Writes `plan.csv` (one row per action) and `plan.json` to `<app_local_data_dir>/exports/plan-<plan_id>/`.
+#[tauri::command]
+pub async fn export_plan(app_handle: AppHandle, plan_id: String) -> Result<Vec<String>, AppError> {
+    let (plan, _) = load_plan(&app_handle, &plan_id)?;
+    let dir = app_handle.path().app_local_data_dir()?.join("exports").join(format!("plan-{}", plan.plan_id));
+    std::fs::create_dir_all(&dir)?;
+
+    let mut csv = String::from("action,channel_id,message_id,timestamp,author_id,emoji,snippet\n");
+    for action in &plan.actions {
+        let kind = match action.kind {
+            PlannedKind::DeleteMessage => "delete_message",
+            PlannedKind::RemoveReaction => "remove_reaction",
+        };
+        let timestamp = chrono::DateTime::from_timestamp_millis(action.timestamp as i64).map(|t| t.to_rfc3339()).unwrap_or_default();
+        let row = [
+            kind.to_string(),
+            action.channel_id.clone(),
+            action.message_id.clone(),
+            timestamp,
+            action.author_id.clone().unwrap_or_default(),
+            action.emoji.clone().unwrap_or_default(),
+            action.snippet.clone(),
+        ];
+        csv.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    Ok(vec![
+        write_file(&dir, "plan.csv", csv)?,
+        write_file(&dir, "plan.json", serde_json::to_vec_pretty(&plan)?)?,
+    ])
+}
+
+/// Performs exactly the actions of a completed simulation, without re-reading any history.
+///
+/// # Logic
+/// `expected_digest` must equal the plan's `digest`, so the UI can only execute the exact plan the
+/// user reviewed. Deletes are idempotent: re-running a partially executed plan reports the
+/// finished part as `already_gone`.
+#[tauri::command]
+pub async fn execute_plan(app_handle: AppHandle, window: tauri::Window, plan_id: String, expected_digest: String) -> Result<TargetedDeleteReport, AppError> {
+    let (plan, status) = load_plan(&app_handle, &plan_id)?;
+    if status != JobStatus::Completed {
+        return Err(AppError { user_message: "The simulation did not finish; resume it before executing its plan.".into(), error_code: "plan_incomplete".into(), ..Default::default() });
+    }
+    if plan.digest != expected_digest {
+        return Err(AppError {
+            user_message: "The plan does not match the one that was reviewed.".into(),
+            error_code: "plan_mismatch".into(),
+            technical_details: Some(format!("expected digest {}, plan has {}", expected_digest, plan.digest)),
+        });
+    }
+
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
+    let op_manager = app_handle.state::<OperationManager>();
+    let archive = if plan.filters.archive { Some(MessageArchive::open(&app_handle)?) } else { None };
+    let attachment_store = if plan.filters.download_attachments {
+        Some(AttachmentStore::open(&app_handle, plan.filters.attachment_max_bytes)?)
+    } else {
+        None
+    };
+    let op = op_manager.begin(&app_handle, "execute_plan", None, true)?;
+    let api_handle = app_handle.state::<ApiHandle>().cancellable(&op.cancel);
+    Logger::info(&app_handle, &format!("[OP] Executing plan {} ({} deletes, {} reactions)", plan.plan_id, plan.delete_count(), plan.reaction_count()), None);
+
+    let mut report = TargetedDeleteReport::default();
+    let mut failure = None;
+    for (i, action) in plan.actions.iter().enumerate() {
+        op.wait_if_paused().await;
+        if op.is_aborted() {
+            report.aborted = true;
+            break;
+        }
+
+        let outcome = match (action.kind, &action.emoji) {
+            (PlannedKind::RemoveReaction, Some(emoji)) => {
+                let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", action.channel_id, action.message_id, emoji);
+                let Ok(attempt) = attempt_delete(&api_handle, &url, &token, is_bearer).await else {
+                    report.aborted = true;
+                    break;
+                };
+                report.count_reaction(attempt.outcome);
+                attempt.outcome
+            }
+            (PlannedKind::RemoveReaction, None) => {
+                report.count_reaction(MessageOutcome::Failed);
+                MessageOutcome::Failed
+            }
+            (PlannedKind::DeleteMessage, _) => {
+                let message = action.message.clone().unwrap_or_else(|| serde_json::json!({ "id": action.message_id }));
+                let attempt = preserve_and_delete(&api_handle, &op, &token, is_bearer, archive.as_ref(), attachment_store.as_ref(), &action.channel_id, &message).await;
+                match attempt {
+                    Ok(attempt) => {
+                        report.count(attempt.outcome);
+                        attempt.outcome
+                    }
+                    Err(e) => {
+                        if e.is_cancelled() { report.aborted = true; } else { failure = Some(e); }
+                        break;
+                    }
+                }
+            }
+        };
+
+        op.set_progress(i as u64 + 1, plan.actions.len() as u64);
+        let _ = window.emit("deletion_progress", serde_json::json!({
+            "job_id": op.job_id,
+            "current": i + 1,
+            "total": plan.actions.len(),
+            "id": action.channel_id,
+            "message_id": action.message_id,
+            "outcome": outcome,
+            "deleted_count": report.deleted,
+            "forbidden_count": report.forbidden,
+            "status": "purging",
+        }));
+    }
+
+    let job_id = op.job_id.clone();
+    drop(op);
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    let _ = window.emit("deletion_complete", serde_json::json!({ "job_id": job_id, "plan_id": plan.plan_id, "deleted_count": report.deleted, "report": report }));
+    Logger::info(&app_handle, &format!("[OP] Plan {} executed. Deleted: {}, reactions removed: {} (failed: {}), already gone: {}, forbidden: {}, failed: {}", plan.plan_id, report.deleted, report.reactions_removed, report.reactions_failed, report.already_gone, report.forbidden, report.failed), None);
+    Ok(report)
+}
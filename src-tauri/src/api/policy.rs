@@ -0,0 +1,117 @@
+// src-tauri/src/api/policy.rs
+
+use std::collections::HashMap;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use crate::api::discord::{fetch_channels, run_purge_job};
+use crate::core::error::AppError;
+use crate::core::journal::{JobJournal, JobStatus};
+use crate::core::logger::Logger;
+use crate::core::policy::{PolicyStore, PolicyTarget, RetentionPolicies, RetentionPolicy};
+
+/// Result of enforcing one policy during `apply_retention_policies`.
+#[derive(Debug, Serialize, Clone)]
+pub struct PolicyRun {
+    pub policy_id: String,
+    pub name: String,
+    pub channel_ids: Vec<String>,
+    /// `None` when the job could not start because another destructive operation was running.
+    pub job_id: Option<String>,
+    pub status: Option<JobStatus>,
+    pub deleted: u64,
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_retention_policies(app_handle: AppHandle) -> Result<RetentionPolicies, AppError> {
+    app_handle.state::<PolicyStore>().load(&app_handle)
+}
+
+/// Creates a policy (empty `policy_id`) or replaces an existing one; its assignments are kept.
+#[tauri::command]
+pub async fn save_retention_policy(app_handle: AppHandle, policy: RetentionPolicy) -> Result<RetentionPolicy, AppError> {
+    let is_new = policy.policy_id.is_empty();
+    let policy = policy.validated()?;
+    app_handle.state::<PolicyStore>().update(&app_handle, |set| {
+        if is_new {
+            set.policies.push(policy.clone());
+        } else {
+            set.get(&policy.policy_id)?;
+            if let Some(existing) = set.policies.iter_mut().find(|p| p.policy_id == policy.policy_id) {
+                *existing = policy.clone();
+            }
+        }
+        Ok(policy)
+    })
+}
+
+#[tauri::command]
+pub async fn delete_retention_policy(app_handle: AppHandle, policy_id: String) -> Result<(), AppError> {
+    app_handle.state::<PolicyStore>().update(&app_handle, |set| set.remove(&policy_id))
+}
+
+/// Attaches a policy to a guild, channel or DM; `policy_id: null` detaches whatever is attached.
+#[tauri::command]
+pub async fn assign_retention_policy(app_handle: AppHandle, target: PolicyTarget, policy_id: Option<String>) -> Result<(), AppError> {
+    app_handle.state::<PolicyStore>().update(&app_handle, |set| set.assign(target, policy_id))
+}
+
+/// Enforces every attached policy in one run: one purge job per policy, covering the channels it governs.
+///
+/// # Logic
+/// Guild assignments are expanded to the guild's current channels and threads at the start of the
+/// run; a guild whose channels cannot be listed (e.g. one we left) is logged and skipped. Jobs run
+/// one after another; aborting one ends the whole run, while a job that stops on an error is
+/// recorded and the next policy still runs. If another destructive operation holds the run slot,
+/// the run stops there and returns the policies enforced so far together with the conflict.
+/// Every job can be resumed like a manual purge.
+#[tauri::command]
+pub async fn apply_retention_policies(app_handle: AppHandle, window: tauri::Window, simulation: bool) -> Result<Vec<PolicyRun>, AppError> {
+    let set = app_handle.state::<PolicyStore>().load(&app_handle)?;
+    let mut guild_channels = HashMap::new();
+    for guild_id in set.guild_ids() {
+        match fetch_channels(app_handle.clone(), Some(guild_id.to_string())).await {
+            Ok(channels) => { guild_channels.insert(guild_id.to_string(), channels.into_iter().map(|c| c.id).collect::<Vec<_>>()); }
+            Err(e) => Logger::warn(&app_handle, &format!("[POLICY] Skipping guild {}: {}", guild_id, e.user_message), None),
+        }
+    }
+
+    let groups = set.channel_groups(&guild_channels);
+    Logger::info(&app_handle, &format!("[POLICY] Applying {} retention policies (Sim: {})", groups.len(), simulation), None);
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let mut runs = Vec::new();
+    for (policy, channel_ids) in groups {
+        let mut journal = JobJournal::new(channel_ids.clone(), policy.filters(now, simulation));
+        Logger::info(&app_handle, &format!("[POLICY] Enforcing '{}' on {} node(s) (Job: {})", policy.name, channel_ids.len(), journal.job_id), None);
+
+        let result = run_purge_job(&app_handle, &window, &mut journal).await;
+        // Another destructive operation holds the run slot; later policies would hit it too.
+        if let Err(e) = &result {
+            if e.error_code == "operation_already_running" {
+                runs.push(PolicyRun {
+                    policy_id: policy.policy_id.clone(),
+                    name: policy.name.clone(),
+                    channel_ids,
+                    job_id: None,
+                    status: None,
+                    deleted: 0,
+                    error: Some(e.user_message.clone()),
+                });
+                break;
+            }
+        }
+        runs.push(PolicyRun {
+            policy_id: policy.policy_id.clone(),
+            name: policy.name.clone(),
+            channel_ids,
+            job_id: Some(journal.job_id.clone()),
+            status: Some(journal.status),
+            deleted: journal.deleted_total,
+            error: result.err().map(|e| e.user_message),
+        });
+        if journal.status == JobStatus::Aborted { break; }
+    }
+
+    Logger::info(&app_handle, &format!("[POLICY] Retention run finished. Items nullified: {}", runs.iter().map(|r| r.deleted).sum::<u64>()), None);
+    Ok(runs)
+}
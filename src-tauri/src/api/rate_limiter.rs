@@ -4,10 +4,15 @@ use tokio::sync::{mpsc, oneshot, Mutex};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
-use reqwest::{Client, Method, Response, header};
+use reqwest::{Client, Method, RequestBuilder, Response, header};
 use crate::core::error::AppError;
 use crate::core::logger::Logger;
+use futures_util::future::BoxFuture;
 use rand::Rng;
+use tokio_util::sync::CancellationToken;
+
+/// Exchanges an OAuth token the API rejected for a fresh one; `None` if it cannot be renewed.
+pub type RenewToken = Arc<dyn Fn(String) -> BoxFuture<'static, Option<String>> + Send + Sync>;
 
 /// Represents a pending API request
 pub struct ApiRequest {
@@ -16,7 +21,11 @@ pub struct ApiRequest {
     pub body: Option<serde_json::Value>,
     pub auth_token: String,
     pub is_bearer: bool,
-    pub response_tx: oneshot::Sender<Result<reqwest::Response, AppError>>,
+    /// Receives the final response together with the number of 429 retries it took.
+    pub response_tx: oneshot::Sender<Result<(reqwest::Response, u32), AppError>>,
+    /// Cancelling it abandons the request wherever it is: waiting on a bucket, sleeping between
+    /// retries, or in flight.
+    pub cancel: Option<CancellationToken>,
 }
 
 /// Information about a rate limit bucket
@@ -45,16 +54,18 @@ pub struct RateLimiterActor {
     buckets: Arc<Mutex<HashMap<String, Arc<Mutex<BucketInfo>>>>>,
     global_reset_at: Arc<Mutex<Instant>>,
     app_handle: tauri::AppHandle,
+    renew: RenewToken,
 }
 
 impl RateLimiterActor {
-    pub fn new(inbox: mpsc::Receiver<ApiRequest>, app_handle: tauri::AppHandle) -> Self {
+    pub fn new(inbox: mpsc::Receiver<ApiRequest>, app_handle: tauri::AppHandle, renew: RenewToken) -> Self {
         Self {
             inbox,
             client: Client::new(),
             buckets: Arc::new(Mutex::new(HashMap::new())),
             global_reset_at: Arc::new(Mutex::new(Instant::now())),
             app_handle,
+            renew,
         }
     }
 
@@ -94,104 +105,145 @@ impl RateLimiterActor {
     pub async fn run(&mut self) {
         Logger::info(&self.app_handle, "[LIM] Engine Dispatcher active", None);
         
-        while let Some(request) = self.inbox.recv().await {
+        while let Some(mut request) = self.inbox.recv().await {
             let client = self.client.clone();
             let buckets_map = self.buckets.clone();
             let global_throttle = self.global_reset_at.clone();
             let app_handle = self.app_handle.clone();
+            let renew = self.renew.clone();
             let route = Self::get_route(&request.url);
 
             tokio::spawn(async move {
-                let mut retry_count = 0;
-                const MAX_RETRIES: u32 = 3;
-
-                loop {
-                    let now = Instant::now();
-
-                    // 1. Global Wait
-                    {
-                        let global = global_throttle.lock().await;
-                        if now < *global {
-                            let wait = *global - now;
-                            tokio::time::sleep(wait).await;
-                            continue;
-                        }
-                    }
+                let cancel = request.cancel.clone().unwrap_or_default();
+                let result = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => Err(AppError::cancelled()),
+                    result = Self::execute(&client, &buckets_map, &global_throttle, &app_handle, &renew, &route, &mut request) => result,
+                };
+                let _ = request.response_tx.send(result);
+            });
+        }
+    }
 
-                    // 2. Bucket Synchronization
-                    let bucket_arc = {
-                        let mut map = buckets_map.lock().await;
-                        map.entry(route.clone()).or_insert_with(|| Arc::new(Mutex::new(BucketInfo::default()))).clone()
-                    };
-
-                    {
-                        let mut bucket = bucket_arc.lock().await;
-                        if now >= bucket.reset_at {
-                            bucket.remaining = bucket.limit;
-                        }
-
-                        if bucket.remaining == 0 {
-                            let wait = bucket.reset_at.saturating_duration_since(now);
-                            if !wait.is_zero() {
-                                Logger::trace(&app_handle, &format!("[LIM] Delaying for bucket '{}'", route), None);
-                                drop(bucket);
-                                tokio::time::sleep(wait + Duration::from_millis(50)).await;
-                                continue;
-                            }
-                        }
-                        bucket.remaining = bucket.remaining.saturating_sub(1);
-                    }
+    /// Waits for the route's bucket, sends the request and retries it after 429s, server errors
+    /// and network failures. Dropping the future abandons the request at whatever await it is on.
+    async fn execute(
+        client: &Client,
+        buckets_map: &Arc<Mutex<HashMap<String, Arc<Mutex<BucketInfo>>>>>,
+        global_throttle: &Arc<Mutex<Instant>>,
+        app_handle: &tauri::AppHandle,
+        renew: &RenewToken,
+        route: &str,
+        request: &mut ApiRequest,
+    ) -> Result<(Response, u32), AppError> {
+        let mut retry_count = 0;
+        let mut token_renewed = false;
+        let mut rate_limited = 0u32;
+        const MAX_RETRIES: u32 = 3;
+
+        loop {
+            let now = Instant::now();
 
-                    // 3. Execution
-                    let mut req_builder = client.request(request.method.clone(), &request.url);
-                    if request.is_bearer {
-                        req_builder = req_builder.bearer_auth(&request.auth_token);
-                    } else {
-                        req_builder = req_builder.header(header::AUTHORIZATION, &request.auth_token);
+            // 1. Global Wait
+            {
+                let global = global_throttle.lock().await;
+                if now < *global {
+                    let wait = *global - now;
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+            }
+
+            // 2. Bucket Synchronization
+            let bucket_arc = {
+                let mut map = buckets_map.lock().await;
+                map.entry(route.to_string()).or_insert_with(|| Arc::new(Mutex::new(BucketInfo::default()))).clone()
+            };
+
+            {
+                let mut bucket = bucket_arc.lock().await;
+                if now >= bucket.reset_at {
+                    bucket.remaining = bucket.limit;
+                }
+
+                if bucket.remaining == 0 {
+                    let wait = bucket.reset_at.saturating_duration_since(now);
+                    if !wait.is_zero() {
+                        Logger::trace(app_handle, &format!("[LIM] Delaying for bucket '{}'", route), None);
+                        drop(bucket);
+                        tokio::time::sleep(wait + Duration::from_millis(50)).await;
+                        continue;
                     }
-                    if let Some(body) = request.body.clone() {
-                        req_builder = req_builder.json(&body);
+                }
+                bucket.remaining = bucket.remaining.saturating_sub(1);
+            }
+
+            // 3. Execution
+            // Strategic Jitter for non-GET requests
+            if request.method != Method::GET {
+                let jitter = rand::thread_rng().gen_range(150..400);
+                tokio::time::sleep(Duration::from_millis(jitter)).await;
+            }
+
+            match Self::send_attempt(client, renew, request, &mut token_renewed).await {
+                Ok(response) => {
+                    let status = response.status();
+                    let is_429 = status.as_u16() == 429;
+                    
+                    Self::process_headers(app_handle, route, &response, &bucket_arc, global_throttle, is_429).await;
+
+                    if is_429 {
+                        rate_limited += 1;
+                        Logger::warn(app_handle, &format!("[LIM] Rate limit hit on {}", route), None);
+                        continue; 
                     }
 
-                    // Strategic Jitter for non-GET requests
-                    if request.method != Method::GET {
-                        let jitter = rand::thread_rng().gen_range(150..400);
-                        tokio::time::sleep(Duration::from_millis(jitter)).await;
+                    if !status.is_success() && status.is_server_error() && retry_count < MAX_RETRIES {
+                        retry_count += 1;
+                        tokio::time::sleep(Duration::from_secs(retry_count as u64)).await;
+                        continue;
                     }
 
-                    match req_builder.send().await {
-                        Ok(response) => {
-                            let status = response.status();
-                            let is_429 = status.as_u16() == 429;
-                            
-                            Self::process_headers(&app_handle, &route, &response, &bucket_arc, &global_throttle, is_429).await;
-
-                            if is_429 {
-                                Logger::warn(&app_handle, &format!("[LIM] Rate limit hit on {}", route), None);
-                                continue; 
-                            }
-
-                            if !status.is_success() && status.is_server_error() && retry_count < MAX_RETRIES {
-                                retry_count += 1;
-                                tokio::time::sleep(Duration::from_secs(retry_count as u64)).await;
-                                continue;
-                            }
-
-                            let _ = request.response_tx.send(Ok(response));
-                            break; 
-                        }
-                        Err(e) => {
-                            if retry_count < MAX_RETRIES {
-                                retry_count += 1;
-                                tokio::time::sleep(Duration::from_secs(retry_count as u64)).await;
-                                continue;
-                            }
-                            let _ = request.response_tx.send(Err(AppError::from(e)));
-                            break;
-                        }
+                    return Ok((response, rate_limited));
+                }
+                Err(e) => {
+                    if retry_count < MAX_RETRIES {
+                        retry_count += 1;
+                        tokio::time::sleep(Duration::from_secs(retry_count as u64)).await;
+                        continue;
                     }
+                    return Err(AppError::from(e));
                 }
-            });
+            }
+        }
+    }
+
+    fn build(client: &Client, request: &ApiRequest) -> RequestBuilder {
+        let mut req_builder = client.request(request.method.clone(), &request.url);
+        if request.is_bearer {
+            req_builder = req_builder.bearer_auth(&request.auth_token);
+        } else {
+            req_builder = req_builder.header(header::AUTHORIZATION, &request.auth_token);
+        }
+        if let Some(body) = request.body.clone() {
+            req_builder = req_builder.json(&body);
+        }
+        req_builder
+    }
+
+    /// Sends the request once. An expired OAuth token is renewed once per request and the request
+    /// replayed straight away with the new one; a 401 after that is returned to the caller.
+    async fn send_attempt(client: &Client, renew: &RenewToken, request: &mut ApiRequest, token_renewed: &mut bool) -> reqwest::Result<Response> {
+        loop {
+            let response = Self::build(client, request).send().await?;
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED && request.is_bearer && !*token_renewed {
+                *token_renewed = true;
+                if let Some(renewed) = renew(request.auth_token.clone()).await {
+                    request.auth_token = renewed;
+                    continue;
+                }
+            }
+            return Ok(response);
         }
     }
 
@@ -250,11 +302,18 @@ impl RateLimiterActor {
 #[derive(Clone)]
 pub struct ApiHandle {
     tx: mpsc::Sender<ApiRequest>,
+    cancel: Option<CancellationToken>,
 }
 
 impl ApiHandle {
     pub fn new(tx: mpsc::Sender<ApiRequest>) -> Self {
-        Self { tx }
+        Self { tx, cancel: None }
+    }
+
+    /// Handle whose requests are all abandoned with a `cancelled` error once `token` is cancelled.
+    /// Jobs pass their operation's token so aborting them does not wait out a rate limit.
+    pub fn cancellable(&self, token: &CancellationToken) -> Self {
+        Self { tx: self.tx.clone(), cancel: Some(token.clone()) }
     }
 
     pub async fn send_request(
@@ -265,6 +324,18 @@ impl ApiHandle {
         auth_token: &str,
         is_bearer: bool,
     ) -> Result<reqwest::Response, AppError> {
+        self.send_request_counted(method, url, body, auth_token, is_bearer).await.map(|(response, _)| response)
+    }
+
+    /// Like `send_request`, but also reports how many times the request was retried after a 429.
+    pub async fn send_request_counted(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<serde_json::Value>,
+        auth_token: &str,
+        is_bearer: bool,
+    ) -> Result<(reqwest::Response, u32), AppError> {
         let (response_tx, response_rx) = oneshot::channel();
         
         let api_request = ApiRequest {
@@ -274,18 +345,129 @@ impl ApiHandle {
             auth_token: auth_token.to_string(),
             is_bearer,
             response_tx,
+            cancel: self.cancel.clone(),
         };
 
-        self.tx.send(api_request).await.map_err(|_| AppError {
-            user_message: "Rate limiter connection failure.".to_string(),
-            error_code: "limiter_offline".to_string(),
-            technical_details: None,
-        })?;
-
-        response_rx.await.map_err(|_| AppError {
-            user_message: "Rate limiter communication timeout.".to_string(),
-            error_code: "limiter_timeout".to_string(),
-            technical_details: None,
-        })?
+        let cancel = self.cancel.clone().unwrap_or_default();
+        if cancel.is_cancelled() {
+            return Err(AppError::cancelled());
+        }
+
+        // The inbox is bounded, so a cancelled job must not stay queued behind it either.
+        tokio::select! {
+            _ = cancel.cancelled() => return Err(AppError::cancelled()),
+            sent = self.tx.send(api_request) => sent.map_err(|_| AppError {
+                user_message: "Rate limiter connection failure.".to_string(),
+                error_code: "limiter_offline".to_string(),
+                technical_details: None,
+            })?,
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => Err(AppError::cancelled()),
+            response = response_rx => response.map_err(|_| AppError {
+                user_message: "Rate limiter communication timeout.".to_string(),
+                error_code: "limiter_timeout".to_string(),
+                technical_details: None,
+            })?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers every request with 200 if its Authorization header is `Bearer fresh`, 401 otherwise,
+    /// and reports the headers it saw.
+    async fn serve() -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buffer).await.unwrap_or(0);
+                    if n == 0 { break; }
+                    request.extend_from_slice(&buffer[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let auth = request.lines().find_map(|l| l.strip_prefix("authorization: ")).unwrap_or_default().to_string();
+                let status = if auth == "bearer fresh" { "200 OK" } else { "401 Unauthorized" };
+                let _ = tx.send(auth);
+                let _ = socket.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        (format!("http://{}/api/v9/users/@me", addr), rx)
+    }
+
+    fn bearer_request(url: &str, token: &str) -> ApiRequest {
+        let (response_tx, _) = oneshot::channel();
+        ApiRequest { method: Method::GET, url: url.to_string(), body: None, auth_token: token.to_string(), is_bearer: true, response_tx, cancel: None }
+    }
+
+    fn renew_to(token: Option<&'static str>, calls: Arc<AtomicUsize>) -> RenewToken {
+        Arc::new(move |_: String| -> BoxFuture<'static, Option<String>> {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { token.map(str::to_string) })
+        })
+    }
+
+    #[tokio::test]
+    async fn an_expired_token_is_renewed_once_and_replayed() {
+        let (url, mut seen) = serve().await;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let renew = renew_to(Some("fresh"), calls.clone());
+        let mut request = bearer_request(&url, "stale");
+        let mut renewed = false;
+
+        let response = RateLimiterActor::send_attempt(&Client::new(), &renew, &mut request, &mut renewed).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(request.auth_token, "fresh");
+        assert_eq!((seen.recv().await.unwrap(), seen.recv().await.unwrap()), ("bearer stale".to_string(), "bearer fresh".to_string()));
+    }
+
+    #[tokio::test]
+    async fn a_second_401_reaches_the_caller() {
+        let (url, mut seen) = serve().await;
+        let calls = Arc::new(AtomicUsize::new(0));
+        // The renewed token is rejected as well.
+        let renew = renew_to(Some("also-stale"), calls.clone());
+        let mut request = bearer_request(&url, "stale");
+        let mut renewed = false;
+
+        let response = RateLimiterActor::send_attempt(&Client::new(), &renew, &mut request, &mut renewed).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(seen.recv().await.unwrap(), "bearer stale");
+        assert_eq!(seen.recv().await.unwrap(), "bearer also-stale");
+        assert!(seen.try_recv().is_err());
+
+        // Later attempts of the same request (e.g. after a 429) do not renew again.
+        let response = RateLimiterActor::send_attempt(&Client::new(), &renew, &mut request, &mut renewed).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn cancelling_resolves_queued_requests() {
+        // Nobody drains the inbox, so the request stays queued until it is cancelled.
+        let (tx, _inbox) = mpsc::channel(1);
+        let token = CancellationToken::new();
+        let handle = ApiHandle::new(tx).cancellable(&token);
+
+        let pending = tokio::spawn(async move {
+            handle.send_request(Method::GET, "https://discord.com/api/v9/users/@me", None, "token", false).await
+        });
+        tokio::task::yield_now().await;
+        token.cancel();
+        let err = pending.await.unwrap().err().unwrap();
+        assert!(err.is_cancelled());
     }
 }
@@ -0,0 +1,159 @@
+// src-tauri/src/api/schedule.rs
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Emitter};
+use crate::api::discord::run_purge_job;
+use crate::core::error::AppError;
+use crate::core::journal::JobJournal;
+use crate::core::logger::Logger;
+use crate::core::schedule::{PurgeSchedule, ScheduleRun, ScheduleStore};
+use crate::core::vault::Vault;
+
+/// Longest the scheduler sleeps between checks, so clock changes and system sleep are noticed.
+const MAX_IDLE: Duration = Duration::from_secs(60);
+
+/// Start failures that say "not now" rather than "this run failed": the run slot is taken, or the
+/// file vault is still waiting for its passphrase.
+const DEFERRING_ERRORS: [&str; 2] = ["operation_already_running", "vault_locked"];
+
+fn schedule_not_found(schedule_id: &str) -> AppError {
+    AppError { user_message: "Schedule not found.".into(), error_code: "schedule_not_found".into(), technical_details: Some(schedule_id.to_string()) }
+}
+
+#[tauri::command]
+pub async fn list_schedules(app_handle: AppHandle) -> Result<Vec<PurgeSchedule>, AppError> {
+    app_handle.state::<ScheduleStore>().list(&app_handle)
+}
+
+/// Creates a schedule (empty `schedule_id`) or replaces the definition of an existing one.
+/// New schedules are bound to the active identity; run history and ownership survive edits.
+#[tauri::command]
+pub async fn save_schedule(app_handle: AppHandle, schedule: PurgeSchedule) -> Result<PurgeSchedule, AppError> {
+    let is_new = schedule.schedule_id.is_empty();
+    let mut schedule = schedule.validated()?;
+    if is_new {
+        schedule.identity_id = Some(Vault::get_active_identity(&app_handle)?.id);
+    }
+
+    app_handle.state::<ScheduleStore>().update(&app_handle, |schedules| {
+        match schedules.iter_mut().find(|s| s.schedule_id == schedule.schedule_id) {
+            Some(existing) => {
+                schedule.identity_id = existing.identity_id.clone();
+                schedule.created_at = existing.created_at;
+                schedule.last_run = existing.last_run.clone();
+                *existing = schedule.clone();
+            }
+            None if is_new => schedules.push(schedule.clone()),
+            None => return Err(schedule_not_found(&schedule.schedule_id)),
+        }
+        Ok(schedule)
+    })
+}
+
+#[tauri::command]
+pub async fn delete_schedule(app_handle: AppHandle, schedule_id: String) -> Result<(), AppError> {
+    app_handle.state::<ScheduleStore>().update(&app_handle, |schedules| {
+        let count = schedules.len();
+        schedules.retain(|s| s.schedule_id != schedule_id);
+        if schedules.len() == count { Err(schedule_not_found(&schedule_id)) } else { Ok(()) }
+    })
+}
+
+/// Runs due schedules for as long as the app is open. Spawned once at startup.
+///
+/// # Logic
+/// Schedules run one after another through `run_purge_job`, so they claim the same run slot as
+/// manual purges. A schedule whose start is deferred (see `DEFERRING_ERRORS`) keeps its due time
+/// and is retried on the next check instead of skipping to its next occurrence.
+pub async fn run_scheduler(app: AppHandle) {
+    let store = app.state::<ScheduleStore>();
+    Logger::info(&app, "[SCHED] Scheduler active", None);
+
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        let due: Vec<String> = match store.list(&app) {
+            Ok(schedules) => schedules.into_iter().filter(|s| s.is_due(now)).map(|s| s.schedule_id).collect(),
+            Err(e) => {
+                Logger::error(&app, "[SCHED] Failed to read schedules", Some(serde_json::json!({ "error": e.to_string() })));
+                Vec::new()
+            }
+        };
+        for schedule_id in due {
+            // Re-read each time: an earlier run may have taken long enough for the user to edit or delete this one.
+            let Some(schedule) = store.list(&app).ok().and_then(|s| s.into_iter().find(|s| s.schedule_id == schedule_id)) else { continue; };
+            if schedule.is_due(chrono::Utc::now().timestamp_millis()) {
+                run_schedule(&app, &store, &schedule).await;
+            }
+        }
+
+        // Deferred schedules are still due; they are retried after `MAX_IDLE` rather than in a busy loop.
+        let now = chrono::Utc::now().timestamp_millis();
+        let next_run = store.list(&app).ok().and_then(|s| s.iter().filter_map(|s| s.next_run).filter(|next| *next > now).min());
+        let idle = next_run.map_or(MAX_IDLE, |next| Duration::from_millis((next - now) as u64).min(MAX_IDLE));
+        tokio::select! {
+            _ = store.changed.notified() => {}
+            _ = tokio::time::sleep(idle) => {}
+        }
+    }
+}
+
+async fn run_schedule(app: &AppHandle, store: &ScheduleStore, schedule: &PurgeSchedule) {
+    let started_at = chrono::Utc::now().timestamp_millis();
+    Logger::info(app, &format!("[SCHED] Running schedule '{}' on {} node(s)", schedule.name, schedule.channel_ids.len()), None);
+    let run = match start_job(app, schedule, started_at).await {
+        Err(e) if DEFERRING_ERRORS.contains(&e.error_code.as_str()) => {
+            Logger::debug(app, &format!("[SCHED] Schedule '{}' deferred: {}", schedule.name, e.user_message), None);
+            return;
+        }
+        Ok((journal, result)) => {
+            let report = journal.report();
+            ScheduleRun {
+                started_at,
+                finished_at: chrono::Utc::now().timestamp_millis(),
+                job_id: Some(journal.job_id),
+                status: Some(journal.status),
+                deleted: report.deleted,
+                failed: report.failed,
+                error: result.err().map(|e| e.user_message),
+            }
+        }
+        Err(e) => ScheduleRun {
+            started_at,
+            finished_at: chrono::Utc::now().timestamp_millis(),
+            job_id: None,
+            status: None,
+            deleted: 0,
+            failed: 0,
+            error: Some(e.user_message),
+        },
+    };
+    Logger::info(app, &format!("[SCHED] Schedule '{}' finished ({:?}). Deleted: {}, failed: {}", schedule.name, run.status, run.deleted, run.failed), run.error.as_ref().map(|e| serde_json::json!({ "error": e })));
+
+    let recorded = store.update(app, |schedules| {
+        if let Some(stored) = schedules.iter_mut().find(|s| s.schedule_id == schedule.schedule_id) {
+            stored.last_run = Some(run.clone());
+            stored.reschedule(chrono::Local::now().naive_local());
+        }
+        Ok(())
+    });
+    if let Err(e) = recorded {
+        Logger::error(app, "[SCHED] Failed to record schedule run", Some(serde_json::json!({ "schedule_id": schedule.schedule_id, "error": e.to_string() })));
+    }
+    let _ = app.emit("schedule_run", serde_json::json!({ "schedule_id": schedule.schedule_id, "run": run }));
+}
+
+/// Creates the run's journal and drives the job. The journal comes back even when the job stops on
+/// an error, so the run records how far it got; an error means the job never started.
+async fn start_job(app: &AppHandle, schedule: &PurgeSchedule, started_at: i64) -> Result<(JobJournal, Result<(), AppError>), AppError> {
+    // Read first: a locked vault fails here, before anything is claimed or written.
+    let identity = Vault::get_active_identity(app)?;
+    if schedule.identity_id.as_ref().is_some_and(|id| *id != identity.id) {
+        return Err(AppError { user_message: "This schedule belongs to an identity that is not active.".into(), error_code: "identity_mismatch".into(), ..Default::default() });
+    }
+    let mut journal = JobJournal::new(schedule.channel_ids.clone(), schedule.resolve_filters(started_at as u64));
+    // The run slot is claimed inside `run_purge_job`, atomically with the conflict check.
+    match run_purge_job(app, app, &mut journal).await {
+        Err(e) if e.error_code == "operation_already_running" => Err(e),
+        result => Ok((journal, result)),
+    }
+}
@@ -0,0 +1,96 @@
+// src-tauri/src/api/search.rs
+
+use std::time::Duration;
+use crate::api::rate_limiter::ApiHandle;
+use crate::core::error::AppError;
+use crate::core::snowflake::SnowflakeWindow;
+
+/// Where a search request is issued. Guild channels are searched through the guild index
+/// (filtered by `channel_id`), DMs and group DMs through their own channel index.
+#[derive(Debug, Clone)]
+pub enum SearchScope {
+    Guild { guild_id: String, channel_id: String },
+    Channel { channel_id: String },
+}
+
+/// Server-side filters for a single search page. Content is deliberately not sent: Discord's index
+/// matches whole words only, so `search_query` is left to the local `ContentFilter`, which matches
+/// substrings and regexes the same way in search and history mode.
+pub struct SearchQuery<'a> {
+    pub author_id: &'a str,
+    pub window: SnowflakeWindow,
+    /// Exclusive upper bound; the id of the oldest message already processed.
+    pub max_id: Option<&'a str>,
+    pub has_attachment: bool,
+}
+
+pub enum SearchPage {
+    /// Hits of this page, newest first. An empty list means the search is exhausted.
+    Ready(Vec<serde_json::Value>),
+    /// Discord answered 202: the index for this scope is still being built.
+    Indexing(Duration),
+    /// Any other non-success status.
+    Failed(reqwest::StatusCode),
+}
+
+/// Looks up the guild owning `channel_id` to decide which search index to query.
+pub async fn resolve_scope(api_handle: &ApiHandle, token: &str, is_bearer: bool, channel_id: &str) -> Result<SearchScope, AppError> {
+    let response = api_handle.send_request(reqwest::Method::GET, &format!("https://discord.com/api/v9/channels/{}", channel_id), None, token, is_bearer).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(AppError { user_message: format!("Failed to resolve node {} (HTTP {})", channel_id, status), error_code: "channel_lookup_failed".into(), ..Default::default() });
+    }
+    let channel: serde_json::Value = response.json().await?;
+    Ok(match channel["guild_id"].as_str() {
+        Some(guild_id) => SearchScope::Guild { guild_id: guild_id.to_string(), channel_id: channel_id.to_string() },
+        None => SearchScope::Channel { channel_id: channel_id.to_string() },
+    })
+}
+
+/// Fetches one page of our own messages from the search index, newest first.
+pub async fn fetch_page(api_handle: &ApiHandle, token: &str, is_bearer: bool, scope: &SearchScope, query: &SearchQuery<'_>) -> Result<SearchPage, AppError> {
+    let mut url = match scope {
+        SearchScope::Guild { guild_id, channel_id } => url::Url::parse_with_params(&format!("https://discord.com/api/v9/guilds/{}/messages/search", guild_id), &[("channel_id", channel_id)])?,
+        SearchScope::Channel { channel_id } => url::Url::parse(&format!("https://discord.com/api/v9/channels/{}/messages/search", channel_id))?,
+    };
+    {
+        let mut params = url.query_pairs_mut();
+        params.append_pair("author_id", query.author_id);
+        params.append_pair("sort_by", "timestamp");
+        params.append_pair("sort_order", "desc");
+        params.append_pair("include_nsfw", "true");
+        if let Some(min_id) = query.window.min_id {
+            params.append_pair("min_id", &min_id.to_string());
+        }
+        // The cursor always lies inside the window once paging has begun, so it takes precedence.
+        if let Some(max_id) = query.max_id {
+            params.append_pair("max_id", max_id);
+        } else if let Some(max_id) = query.window.max_id {
+            params.append_pair("max_id", &max_id.to_string());
+        }
+        if query.has_attachment {
+            params.append_pair("has", "attachment");
+        }
+    }
+
+    let response = api_handle.send_request(reqwest::Method::GET, url.as_str(), None, token, is_bearer).await?;
+    let status = response.status();
+    if status.as_u16() == 202 {
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let retry_after = body["retry_after"].as_f64().unwrap_or(2.0).max(0.5);
+        return Ok(SearchPage::Indexing(Duration::from_secs_f64(retry_after)));
+    }
+    if !status.is_success() {
+        return Ok(SearchPage::Failed(status));
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    let hits = body["messages"].as_array().map(|groups| {
+        groups.iter().filter_map(|group| {
+            // Each result is a group of context messages; the matching one is flagged with `hit`.
+            let group = group.as_array()?;
+            group.iter().find(|m| m["hit"].as_bool() == Some(true)).or_else(|| group.first()).cloned()
+        }).collect()
+    }).unwrap_or_default();
+    Ok(SearchPage::Ready(hits))
+}
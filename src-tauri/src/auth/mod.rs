@@ -1,5 +1,7 @@
 // src-tauri/src/auth/mod.rs
 
+pub mod session;
+
 use tauri::{AppHandle, Window, Emitter, Manager};
 use tokio::{sync::oneshot, io::{AsyncReadExt, AsyncWriteExt}, time::{timeout, Duration}};
 use url::Url;
@@ -12,9 +14,11 @@ use oauth2::{
 use serde::{Serialize, Deserialize};
 use tauri_plugin_opener::OpenerExt;
 use crate::core::error::AppError;
-use crate::core::vault::{Vault, DiscordIdentity};
+use crate::core::vault::{Vault, VaultState, VaultStatus, DiscordIdentity, PublicIdentity};
+use crate::core::settings::{AppSettings, OAuthSettings, VaultBackendKind};
 use crate::api::rate_limiter::ApiHandle;
 use crate::core::logger::Logger;
+use crate::auth::session::TokenGrant;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::protocol::Message;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
@@ -30,6 +34,8 @@ use tracing::{debug, trace};
 #[derive(Default)]
 pub struct AuthState {
     pub qr_cancel_token: Mutex<Option<CancellationToken>>,
+    /// Superseded OAuth access tokens mapped to their replacement; also serializes refreshes.
+    pub renewed_tokens: Mutex<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -39,6 +45,25 @@ pub struct DiscordStatus {
     pub browser_detected: bool,
 }
 
+/// Whether Discord was told to invalidate a removed identity's OAuth grant.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Revocation {
+    /// User tokens have no revocation endpoint; only the local copy is destroyed.
+    NotApplicable,
+    Revoked,
+    /// The local copy was deleted but the grant may still be valid at Discord.
+    Failed,
+}
+
+/// Outcome of `remove_identity` / `logout`. Local deletion always happens, even when revocation fails.
+#[derive(Debug, Serialize, Clone)]
+pub struct RemovalReport {
+    pub id: String,
+    pub revocation: Revocation,
+    pub revocation_error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiscordUser {
     pub id: String,
@@ -51,6 +76,7 @@ pub struct DiscordUser {
 pub async fn login_with_rpc(app_handle: AppHandle, window: Window) -> Result<DiscordUser, AppError> {
     Logger::info(&app_handle, "[RPC] Handshake sequence started.", None);
     let client_id = Vault::get_credential(&app_handle, "client_id")?;
+    let oauth_settings = AppSettings::load(&app_handle)?.oauth;
     
     let port = (6463..=6472).find(|p| std::net::TcpStream::connect(format!("127.0.0.1:{}", p)).is_ok());
     let port = port.ok_or_else(|| AppError { user_message: "Discord desktop client not detected.".into(), ..Default::default() })?;
@@ -78,7 +104,7 @@ pub async fn login_with_rpc(app_handle: AppHandle, window: Window) -> Result<Dis
     let nonce = Uuid::new_v4().to_string();
     let auth_payload = serde_json::json!({
         "cmd": "AUTHORIZE",
-        "args": { "client_id": client_id, "scopes": ["identify", "guilds"], "prompt": "none" },
+        "args": { "client_id": client_id, "scopes": oauth_settings.scopes(), "prompt": "none" },
         "nonce": nonce
     });
     
@@ -127,7 +153,7 @@ pub async fn login_with_rpc(app_handle: AppHandle, window: Window) -> Result<Dis
             ("client_secret", &client_secret),
             ("grant_type", &"authorization_code".to_string()),
             ("code", &code),
-            ("redirect_uri", &"http://127.0.0.1".to_string()),
+            ("redirect_uri", &OAuthSettings::RPC_REDIRECT_URI.to_string()),
         ])
         .send().await?;
     
@@ -139,8 +165,8 @@ pub async fn login_with_rpc(app_handle: AppHandle, window: Window) -> Result<Dis
         return Err(AppError { user_message: "Token exchange failed.".into(), technical_details: Some(res_json.to_string()), ..Default::default() });
     }
 
-    let token = res_json["access_token"].as_str().ok_or_else(|| AppError { user_message: "Access token missing in response.".into(), ..Default::default() })?;
-    login_with_token_internal(app_handle, window, token.to_string(), true).await
+    let grant = TokenGrant::from_response(&res_json)?;
+    login_with_token_internal(app_handle, window, grant, true).await
 }
 
 #[tauri::command]
@@ -195,7 +221,7 @@ pub async fn start_qr_login_flow(app_handle: AppHandle, window: Window, state: t
                                     Some("finish") => {
                                         Logger::info(&app_handle_clone, "[QR] Authorized via mobile", None);
                                         if let Some(token) = p["token"].as_str() {
-                                            let _ = login_with_token_internal(app_handle_clone.clone(), window_clone.clone(), token.to_string(), false).await;
+                                            let _ = login_with_token_internal(app_handle_clone.clone(), window_clone.clone(), TokenGrant::bare(token.to_string()), false).await;
                                         }
                                         break;
                                     },
@@ -237,11 +263,11 @@ pub async fn check_discord_status() -> Result<DiscordStatus, AppError> {
 
 #[tauri::command]
 pub async fn login_with_user_token(app_handle: AppHandle, window: Window, token: String) -> Result<DiscordUser, AppError> {
-    login_with_token_internal(app_handle, window, token, false).await
+    login_with_token_internal(app_handle, window, TokenGrant::bare(token), false).await
 }
 
-async fn login_with_token_internal(app_handle: AppHandle, window: Window, token: String, is_oauth: bool) -> Result<DiscordUser, AppError> {
-    let token = token.trim().trim_start_matches("Bearer ").trim_matches('"').to_string();
+async fn login_with_token_internal(app_handle: AppHandle, window: Window, grant: TokenGrant, is_oauth: bool) -> Result<DiscordUser, AppError> {
+    let token = grant.access_token.trim().trim_start_matches("Bearer ").trim_matches('"').to_string();
     let user_profile = validate_token(&app_handle, &token, is_oauth).await?;
     
     Vault::save_identity(&app_handle, DiscordIdentity {
@@ -249,6 +275,10 @@ async fn login_with_token_internal(app_handle: AppHandle, window: Window, token:
         username: user_profile.username.clone(),
         token: token.clone(),
         is_oauth,
+        refresh_token: grant.refresh_token,
+        expires_at: grant.expires_at,
+        avatar: user_profile.avatar.clone(),
+        last_validated: Some(chrono::Utc::now().timestamp_millis()),
     })?;
     
     let _ = window.emit("auth_success", user_profile.clone());
@@ -274,21 +304,118 @@ pub async fn save_discord_credentials(app_handle: AppHandle, client_id: String,
 }
 
 #[tauri::command]
-pub async fn list_identities(app_handle: AppHandle) -> Result<Vec<DiscordIdentity>, AppError> {
-    Ok(Vault::list_identities(&app_handle))
+pub async fn list_identities(app_handle: AppHandle) -> Result<Vec<PublicIdentity>, AppError> {
+    Vault::list_identities(&app_handle)
 }
 
 #[tauri::command]
 pub async fn switch_identity(app_handle: AppHandle, window: Window, id: String) -> Result<DiscordUser, AppError> {
-    let identities = Vault::list_identities(&app_handle);
-    let identity = identities.iter().find(|i| i.id == id).ok_or_else(|| AppError { user_message: "Identity not found.".into(), ..Default::default() })?;
+    let previous = Vault::get_active_identity(&app_handle).ok();
+    Vault::set_active_identity(&app_handle, &id)?;
+    let identity = Vault::get_active_identity(&app_handle)?;
     Logger::info(&app_handle, &format!("[Auth] Switching to identity: {}", identity.username), None);
-    login_with_token_internal(app_handle, window, identity.token.clone(), identity.is_oauth).await
+
+    let (token, is_oauth) = session::active_token(&app_handle).await?;
+    let result = login_with_token_internal(app_handle.clone(), window, TokenGrant::bare(token), is_oauth).await;
+    if result.is_err() {
+        if let Some(previous) = previous {
+            // Keep the last working session active if the target identity no longer validates.
+            let _ = Vault::set_active_identity(&app_handle, &previous.id);
+        }
+    }
+    result
 }
 
 #[tauri::command]
-pub async fn remove_identity(app_handle: AppHandle, id: String) -> Result<(), AppError> {
-    Vault::remove_identity(&app_handle, &id)
+pub async fn remove_identity(app_handle: AppHandle, id: String) -> Result<RemovalReport, AppError> {
+    revoke_and_remove(&app_handle, &id).await
+}
+
+/// Signs out of the active identity: revokes its OAuth grant and deletes it from the vault.
+#[tauri::command]
+pub async fn logout(app_handle: AppHandle) -> Result<RemovalReport, AppError> {
+    let identity = Vault::get_active_identity(&app_handle)?;
+    Logger::info(&app_handle, &format!("[Auth] Logging out of identity: {}", identity.username), None);
+    revoke_and_remove(&app_handle, &identity.id).await
+}
+
+async fn revoke_and_remove(app_handle: &AppHandle, id: &str) -> Result<RemovalReport, AppError> {
+    let (revocation, revocation_error) = match Vault::get_identity(app_handle, id) {
+        Ok(identity) if identity.is_oauth => match session::revoke(app_handle, &identity).await {
+            Ok(()) => (Revocation::Revoked, None),
+            Err(e) => {
+                Logger::warn(app_handle, "[OAuth] Token revocation failed, removing local copy anyway", Some(serde_json::json!({ "id": id, "error": e.to_string() })));
+                (Revocation::Failed, Some(e.user_message))
+            }
+        },
+        _ => (Revocation::NotApplicable, None),
+    };
+
+    Vault::remove_identity(app_handle, id)?;
+    Logger::info(app_handle, &format!("[Vault] Identity {} removed ({:?})", id, revocation), None);
+    Ok(RemovalReport { id: id.to_string(), revocation, revocation_error })
+}
+
+#[tauri::command]
+pub async fn get_vault_status(app_handle: AppHandle) -> Result<VaultStatus, AppError> {
+    Vault::status(&app_handle)
+}
+
+#[tauri::command]
+pub async fn unlock_vault(app_handle: AppHandle, passphrase: String) -> Result<VaultStatus, AppError> {
+    app_handle.state::<VaultState>().unlock_file(&passphrase)?;
+    Logger::info(&app_handle, "[Vault] Encrypted file vault unlocked", None);
+    Vault::status(&app_handle)
+}
+
+#[tauri::command]
+pub async fn set_vault_backend(app_handle: AppHandle, backend: VaultBackendKind) -> Result<VaultStatus, AppError> {
+    let mut settings = AppSettings::load(&app_handle)?;
+    settings.vault_backend = backend;
+    settings.save(&app_handle)?;
+    app_handle.state::<VaultState>().select(backend);
+    Logger::info(&app_handle, &format!("[Vault] Backend preference set to {:?}", backend), None);
+    Vault::status(&app_handle)
+}
+
+/// Binds the OAuth callback listener to the first free port of the configured range.
+/// The whole range is retried a few times to ride out sockets still closing from a previous attempt.
+async fn bind_callback_socket(app_handle: &AppHandle, settings: &OAuthSettings) -> Result<(Socket, u16), AppError> {
+    for attempt in 1..=3 {
+        for port in settings.ports() {
+            let addr = SocketAddr::from(([127, 0, 0, 1], port));
+            let s = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP))?;
+            s.set_reuse_address(true)?;
+            if s.bind(&addr.into()).is_ok() {
+                s.listen(128)?;
+                Logger::debug(app_handle, &format!("[OAuth] Callback bound to {}", addr), None);
+                return Ok((s, port));
+            }
+        }
+        Logger::warn(app_handle, &format!("[OAuth] Callback ports busy, retrying... ({}/3)", attempt), None);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    Logger::error(app_handle, "[OAuth] Failed to bind callback port", None);
+    Err(AppError {
+        user_message: format!("Authorization ports ({}-{}) are all in use.", settings.callback_port_start, settings.callback_port_end),
+        error_code: "callback_port_unavailable".into(),
+        ..Default::default()
+    })
+}
+
+#[tauri::command]
+pub async fn get_oauth_settings(app_handle: AppHandle) -> Result<OAuthSettings, AppError> {
+    Ok(AppSettings::load(&app_handle)?.oauth)
+}
+
+#[tauri::command]
+pub async fn set_oauth_settings(app_handle: AppHandle, oauth: OAuthSettings) -> Result<OAuthSettings, AppError> {
+    let mut settings = AppSettings::load(&app_handle)?;
+    settings.oauth = oauth.validated()?;
+    settings.save(&app_handle)?;
+    Logger::info(&app_handle, "[OAuth] Client settings updated", Some(serde_json::json!({ "ports": [settings.oauth.callback_port_start, settings.oauth.callback_port_end], "scopes": settings.oauth.scopes() })));
+    Ok(settings.oauth)
 }
 
 #[tauri::command]
@@ -304,40 +431,20 @@ pub async fn start_oauth_flow(app_handle: AppHandle, window: Window) -> Result<D
         Some(TokenUrl::new("https://discord.com/api/v9/oauth2/token".to_string()).unwrap())
     );
     
+    let oauth_settings = AppSettings::load(&app_handle)?.oauth;
+    let (socket, port) = bind_callback_socket(&app_handle, &oauth_settings).await?;
+    let redirect_uri = OAuthSettings::redirect_uri(port);
+
     let (pkce_ch, pkce_ver) = PkceCodeChallenge::new_random_sha256();
-    let port = 58123;
-    let client = client.set_redirect_uri(RedirectUrl::new(format!("http://127.0.0.1:{}", port)).unwrap());
+    let client = client.set_redirect_uri(RedirectUrl::new(redirect_uri.clone())?);
     let (auth_url, csrf) = client.authorize_url(CsrfToken::new_random)
-        .add_scope(oauth2::Scope::new("identify".into()))
-        .add_scope(oauth2::Scope::new("guilds".into()))
+        .add_scopes(oauth_settings.scopes().into_iter().map(oauth2::Scope::new))
         .set_pkce_challenge(pkce_ch)
         .url();
 
     let (tx, rx) = oneshot::channel::<String>();
     let csrf_secret = csrf.secret().clone();
-    
-    let addr = format!("127.0.0.1:{}", port).parse::<SocketAddr>().map_err(|e| AppError { user_message: "Invalid bind address.".into(), technical_details: Some(e.to_string()), ..Default::default() })?;
-    
-    Logger::debug(&app_handle, &format!("[OAuth] Binding callback to {}", addr), None);
-    let mut socket = None;
-    for i in 0..3 {
-        let s = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP))?;
-        s.set_reuse_address(true)?;
-        #[cfg(not(windows))]
-        s.set_reuse_port(true)?;
-        if s.bind(&addr.into()).is_ok() {
-            s.listen(128)?;
-            socket = Some(s);
-            break;
-        }
-        Logger::warn(&app_handle, &format!("[OAuth] Port busy, retrying... ({}/3)", i+1), None);
-        tokio::time::sleep(Duration::from_millis(500)).await;
-    }
-
-    let socket = socket.ok_or_else(|| {
-        Logger::error(&app_handle, "[OAuth] Failed to bind callback port", None);
-        AppError { user_message: "Authorization port (58123) is already in use.".into(), ..Default::default() }
-    })?;
+    let _ = window.emit("oauth_callback_ready", serde_json::json!({ "port": port, "redirect_uri": redirect_uri }));
 
     let listener: std::net::TcpListener = socket.into();
     let app_clone = app_handle.clone();
@@ -381,7 +488,12 @@ pub async fn start_oauth_flow(app_handle: AppHandle, window: Window) -> Result<D
     match token_res {
         Ok(res) => {
             Logger::info(&app_handle, "[OAuth] Token exchange successful", None);
-            login_with_token_internal(app_handle, window, res.access_token().secret().to_string(), true).await
+            let grant = TokenGrant::from_expires_in(
+                res.access_token().secret().to_string(),
+                res.refresh_token().map(|t| t.secret().to_string()),
+                res.expires_in().map(|d| d.as_secs()),
+            );
+            login_with_token_internal(app_handle, window, grant, true).await
         },
         Err(e) => {
             Logger::error(&app_handle, "[OAuth] Token exchange failed", Some(serde_json::json!({ "error": format!("{:?}", e) })));
@@ -392,8 +504,48 @@ pub async fn start_oauth_flow(app_handle: AppHandle, window: Window) -> Result<D
 
 #[tauri::command]
 pub async fn get_current_user(app_handle: AppHandle, window: Window) -> Result<DiscordUser, AppError> {
-    let (token, is_bearer) = Vault::get_active_token(&app_handle)?;
+    let (token, is_bearer) = session::active_token(&app_handle).await?;
     let user_profile = validate_token(&app_handle, &token, is_bearer).await?;
     let _ = window.emit("auth_success", user_profile.clone());
     Ok(user_profile)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains_token_field(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::Object(map) => map.iter().any(|(k, v)| k.contains("token") || contains_token_field(v)),
+            serde_json::Value::Array(items) => items.iter().any(contains_token_field),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn command_responses_never_contain_tokens() {
+        let identity = DiscordIdentity {
+            id: "80351110224678912".into(),
+            username: "nelly".into(),
+            token: "mfa.secret-token".into(),
+            is_oauth: true,
+            refresh_token: Some("refresh-secret".into()),
+            expires_at: Some(1_700_604_800_000),
+            avatar: Some("8342729096ea3675442027381ff50dfe".into()),
+            last_validated: Some(1_700_000_000_000),
+        };
+        let user = DiscordUser { id: identity.id.clone(), username: identity.username.clone(), avatar: identity.avatar.clone(), email: None };
+        let status = DiscordStatus { is_running: true, rpc_available: false, browser_detected: true };
+
+        let responses = [
+            serde_json::to_value(vec![PublicIdentity::from(&identity)]).unwrap(),
+            serde_json::to_value(&user).unwrap(),
+            serde_json::to_value(&status).unwrap(),
+        ];
+        for response in &responses {
+            assert!(!contains_token_field(response), "token leaked in {}", response);
+            assert!(!response.to_string().contains(&identity.token));
+            assert!(!response.to_string().contains("refresh-secret"));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// src-tauri/src/auth/session.rs
+
+use tauri::{AppHandle, Manager};
+use crate::auth::AuthState;
+use crate::core::error::AppError;
+use crate::core::logger::Logger;
+use crate::core::vault::{Vault, DiscordIdentity};
+
+/// Access tokens closer than this to their expiry are renewed before use.
+const REFRESH_MARGIN_MS: i64 = 5 * 60 * 1000;
+const TOKEN_URL: &str = "https://discord.com/api/v9/oauth2/token";
+const REVOKE_URL: &str = "https://discord.com/api/v9/oauth2/token/revoke";
+
+/// Token pair returned by Discord's OAuth2 token endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct TokenGrant {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix milliseconds after which `access_token` is no longer accepted.
+    pub expires_at: Option<i64>,
+}
+
+impl TokenGrant {
+    /// A grant for credentials that never expire and cannot be refreshed (user tokens).
+    pub fn bare(access_token: String) -> Self {
+        Self { access_token, ..Default::default() }
+    }
+
+    pub fn from_expires_in(access_token: String, refresh_token: Option<String>, expires_in_secs: Option<u64>) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        Self {
+            access_token,
+            refresh_token,
+            expires_at: expires_in_secs.map(|secs| now.saturating_add((secs as i64).saturating_mul(1000))),
+        }
+    }
+
+    /// Parses a raw `/oauth2/token` JSON response.
+    pub fn from_response(body: &serde_json::Value) -> Result<Self, AppError> {
+        let access_token = body["access_token"].as_str().ok_or_else(|| AppError {
+            user_message: "Access token missing in response.".into(),
+            error_code: "oauth_error".into(),
+            ..Default::default()
+        })?;
+        Ok(Self::from_expires_in(
+            access_token.to_string(),
+            body["refresh_token"].as_str().map(|s| s.to_string()),
+            body["expires_in"].as_u64(),
+        ))
+    }
+}
+
+/// Returns the active token, renewing an OAuth access token first if it is about to expire.
+/// Commands should obtain their credentials here rather than from the vault directly.
+pub async fn active_token(app: &AppHandle) -> Result<(String, bool), AppError> {
+    let session = Vault::get_active_session(app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    if session.is_oauth && session.expires_at.is_some_and(|at| at - REFRESH_MARGIN_MS <= now) {
+        if let Some(renewed) = renew(app, &session.token).await? {
+            return Ok((renewed, true));
+        }
+    }
+    Ok((session.token, session.is_oauth))
+}
+
+/// Exchanges the active identity's refresh token for a new token pair and persists it.
+///
+/// # Logic
+/// `stale_token` is the access token the caller saw rejected (or about to expire). Refreshes are
+/// serialized, and every superseded token is remembered for the lifetime of the process, so tasks
+/// still holding an old token (e.g. a long purge job) get its replacement without another round trip.
+/// Returns `None` when the token does not belong to the active identity or cannot be refreshed
+/// (user token, no refresh token, or the grant was revoked).
+pub async fn renew(app: &AppHandle, stale_token: &str) -> Result<Option<String>, AppError> {
+    let auth_state = app.state::<AuthState>();
+    let mut renewed = auth_state.renewed_tokens.lock().await;
+    if let Some(replacement) = renewed.get(stale_token) {
+        return Ok(Some(replacement.clone()));
+    }
+
+    let session = Vault::get_active_session(app)?;
+    if session.token != stale_token {
+        return Ok(None);
+    }
+    let Some(refresh_token) = session.refresh_token.filter(|_| session.is_oauth) else {
+        return Ok(None);
+    };
+
+    Logger::info(app, "[OAuth] Access token expiring, refreshing session...", None);
+    let client_id = Vault::get_credential(app, "client_id")?;
+    let client_secret = Vault::get_credential(app, "client_secret")?;
+    let res = reqwest::Client::new().post(TOKEN_URL)
+        .form(&[
+            ("client_id", client_id.as_str()),
+            ("client_secret", client_secret.as_str()),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+        ])
+        .send().await?;
+
+    let status = res.status();
+    let body = res.json::<serde_json::Value>().await.unwrap_or_default();
+    if !status.is_success() {
+        Logger::error(app, "[OAuth] Token refresh failed", Some(serde_json::json!({ "status": status.as_u16(), "error": body["error"] })));
+        return Ok(None);
+    }
+
+    let grant = TokenGrant::from_response(&body)?;
+    Vault::update_tokens(app, &session.id, &grant.access_token, grant.refresh_token.as_deref(), grant.expires_at)?;
+    for replacement in renewed.values_mut().filter(|t| t.as_str() == stale_token) {
+        *replacement = grant.access_token.clone();
+    }
+    renewed.insert(stale_token.to_string(), grant.access_token.clone());
+    Logger::info(app, "[OAuth] Session refreshed", None);
+    Ok(Some(grant.access_token))
+}
+
+/// Asks Discord to invalidate an OAuth grant using the stored client credentials.
+/// Revoking the refresh token ends the whole grant; the access token is revoked as well so it
+/// stops working immediately even if Discord keeps the grant alive.
+pub async fn revoke(app: &AppHandle, identity: &DiscordIdentity) -> Result<(), AppError> {
+    let client_id = Vault::get_credential(app, "client_id")?;
+    let client_secret = Vault::get_credential(app, "client_secret")?;
+    let client = reqwest::Client::new();
+
+    let tokens = identity.refresh_token.iter().map(|t| (t, "refresh_token"))
+        .chain(std::iter::once((&identity.token, "access_token")));
+    for (token, hint) in tokens {
+        let res = client.post(REVOKE_URL)
+            .form(&[
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+                ("token", token.as_str()),
+                ("token_type_hint", hint),
+            ])
+            .send().await?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError {
+                user_message: format!("Discord rejected the token revocation (HTTP {}).", status),
+                error_code: "revocation_failed".into(),
+                technical_details: Some(body),
+            });
+        }
+    }
+    Logger::info(app, &format!("[OAuth] Grant revoked for identity {}", identity.username), None);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// src-tauri/src/core/archive.rs
+
+use std::io::Write;
+use std::path::PathBuf;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use crate::core::error::AppError;
+
+/// Location and footprint of the archive files touched by a job, reported in `deletion_complete`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveSummary {
+    pub path: String,
+    pub size_bytes: u64,
+    pub archived_count: u64,
+}
+
+/// Private record of purged messages, one JSON Lines file per channel under
+/// `<app_local_data_dir>/archives/<channel_id>.jsonl`.
+///
+/// # Logic
+/// Each message is appended verbatim (content, embeds, attachment metadata, reactions and
+/// `message_reference`) and flushed to disk *before* its DELETE is sent. Archives accumulate across
+/// jobs; a crash between the append and the DELETE can leave a duplicate line after `resume_job`.
+pub struct MessageArchive {
+    dir: PathBuf,
+}
+
+impl MessageArchive {
+    const DIR_NAME: &'static str = "archives";
+
+    pub fn open(app: &AppHandle) -> Result<Self, AppError> {
+        let dir = app.path().app_local_data_dir()?.join(Self::DIR_NAME);
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, channel_id: &str) -> Result<PathBuf, AppError> {
+        // Channel ids are snowflakes; anything else could escape the archive directory.
+        if channel_id.is_empty() || !channel_id.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AppError {
+                user_message: "Invalid channel identifier.".into(),
+                error_code: "invalid_channel_id".into(),
+                technical_details: Some(channel_id.to_string()),
+            });
+        }
+        Ok(self.dir.join(format!("{}.jsonl", channel_id)))
+    }
+
+    /// Appends one message to the channel's archive and syncs it to disk.
+    pub fn append(&self, channel_id: &str, message: &serde_json::Value) -> Result<(), AppError> {
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(self.path(channel_id)?)?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Sums the archive files of `channel_ids` that exist on disk.
+    pub fn summary<'a>(&self, channel_ids: impl IntoIterator<Item = &'a str>, archived_count: u64) -> ArchiveSummary {
+        let size_bytes = channel_ids.into_iter()
+            .filter_map(|id| self.path(id).ok())
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum();
+        ArchiveSummary { path: self.dir.to_string_lossy().into_owned(), size_bytes, archived_count }
+    }
+}
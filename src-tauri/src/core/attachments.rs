@@ -0,0 +1,351 @@
+// src-tauri/src/core/attachments.rs
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, Semaphore};
+use tokio_util::sync::CancellationToken;
+use futures_util::future::join_all;
+use ring::digest::{Context, SHA256};
+use crate::core::error::AppError;
+use crate::core::op_manager::OperationState;
+
+/// Fate of one media URL, recorded in the manifest.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Stored,
+    SkippedTooLarge,
+    Failed,
+}
+
+/// One line of `manifest.jsonl`, linking a message to the stored file.
+#[derive(Debug, Serialize, Clone)]
+pub struct ManifestEntry {
+    pub message_id: String,
+    pub channel_id: String,
+    pub source_url: String,
+    pub status: DownloadStatus,
+    pub sha256: Option<String>,
+    pub size_bytes: Option<u64>,
+    /// Path relative to the attachment store root (`objects/<sha256>`).
+    pub file: Option<String>,
+    pub error: Option<String>,
+    pub recorded_at: i64,
+}
+
+/// Content-addressed store for attachment and embed media of purged messages, rooted at
+/// `<app_local_data_dir>/archives/attachments`.
+///
+/// # Logic
+/// Files stream into `partial/<key>.part`, where the key is derived from the URL path (CDN query
+/// signatures change between fetches), so an interrupted download resumes with a `Range` request.
+/// Completed files are hashed and moved to `objects/<sha256>`; identical media is stored once.
+/// Every outcome, including skips and failures, is appended to `manifest.jsonl`. Downloads wait
+/// while their operation is paused and stop mid-transfer when it is aborted, keeping the partial
+/// file for the next attempt.
+pub struct AttachmentStore {
+    root: PathBuf,
+    client: reqwest::Client,
+    permits: Semaphore,
+    max_bytes: u64,
+    manifest: Mutex<()>,
+}
+
+impl AttachmentStore {
+    const DIR_NAME: &'static str = "attachments";
+    const MAX_CONCURRENT: usize = 4;
+    pub const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+    pub fn open(app: &AppHandle, max_bytes: Option<u64>) -> Result<Self, AppError> {
+        Self::open_at(app.path().app_local_data_dir()?.join("archives").join(Self::DIR_NAME), max_bytes)
+    }
+
+    pub(crate) fn open_at(root: PathBuf, max_bytes: Option<u64>) -> Result<Self, AppError> {
+        std::fs::create_dir_all(root.join("partial"))?;
+        std::fs::create_dir_all(root.join("objects"))?;
+        Ok(Self {
+            root,
+            client: reqwest::Client::new(),
+            permits: Semaphore::new(Self::MAX_CONCURRENT),
+            max_bytes: max_bytes.unwrap_or(Self::DEFAULT_MAX_BYTES),
+            manifest: Mutex::new(()),
+        })
+    }
+
+    /// Attachment and embed media URLs of a message, paired with the size Discord reports (if any).
+    pub fn media_urls(message: &serde_json::Value) -> Vec<(String, Option<u64>)> {
+        let mut urls: Vec<(String, Option<u64>)> = message["attachments"].as_array().into_iter().flatten()
+            .filter_map(|a| a["url"].as_str().map(|u| (u.to_string(), a["size"].as_u64())))
+            .collect();
+        for embed in message["embeds"].as_array().into_iter().flatten() {
+            for kind in ["image", "thumbnail", "video"] {
+                if let Some(url) = embed[kind]["url"].as_str().filter(|u| u.starts_with("https://")) {
+                    if !urls.iter().any(|(u, _)| u == url) {
+                        urls.push((url.to_string(), None));
+                    }
+                }
+            }
+        }
+        urls
+    }
+
+    /// Downloads every media URL of `message` (at most `MAX_CONCURRENT` at a time across callers)
+    /// and records the outcomes. Returns the number of files that could not be stored, or a
+    /// `cancelled` error if `op` was aborted; downloads cut short that way are not recorded.
+    pub async fn archive_message(&self, op: &OperationState, channel_id: &str, message: &serde_json::Value) -> Result<usize, AppError> {
+        let message_id = message["id"].as_str().unwrap_or_default();
+        let urls = Self::media_urls(message);
+        let entries = join_all(urls.into_iter().map(|(url, reported_size)| async move {
+            op.wait_if_paused().await;
+            let _permit = self.permits.acquire().await;
+            if op.cancel.is_cancelled() { return None; }
+            let mut entry = ManifestEntry {
+                message_id: message_id.to_string(),
+                channel_id: channel_id.to_string(),
+                source_url: url.clone(),
+                status: DownloadStatus::Stored,
+                sha256: None,
+                size_bytes: None,
+                file: None,
+                error: None,
+                recorded_at: chrono::Utc::now().timestamp_millis(),
+            };
+            if reported_size.is_some_and(|size| size > self.max_bytes) {
+                entry.status = DownloadStatus::SkippedTooLarge;
+                entry.size_bytes = reported_size;
+                return Some(entry);
+            }
+            match self.download(&url, &op.cancel).await {
+                Ok(Some((sha256, size))) => {
+                    entry.file = Some(format!("objects/{}", sha256));
+                    entry.sha256 = Some(sha256);
+                    entry.size_bytes = Some(size);
+                }
+                Ok(None) => entry.status = DownloadStatus::SkippedTooLarge,
+                Err(e) if e.is_cancelled() => return None,
+                Err(e) => {
+                    entry.status = DownloadStatus::Failed;
+                    entry.error = Some(e.to_string());
+                }
+            }
+            Some(entry)
+        })).await;
+
+        let entries: Vec<ManifestEntry> = entries.into_iter().flatten().collect();
+        let failed = entries.iter().filter(|e| e.status == DownloadStatus::Failed).count();
+        self.record(&entries).await?;
+        if op.cancel.is_cancelled() {
+            return Err(AppError::cancelled());
+        }
+        Ok(failed)
+    }
+
+    /// Streams `url` into its partial file, resuming if one exists. Returns `None` if the file exceeds
+    /// the size cap, or a `cancelled` error as soon as `cancel` fires.
+    async fn download(&self, url: &str, cancel: &CancellationToken) -> Result<Option<(String, u64)>, AppError> {
+        let partial = self.root.join("partial").join(format!("{}.part", Self::key(url)?));
+        let offset = tokio::fs::metadata(&partial).await.map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let response = tokio::select! {
+            _ = cancel.cancelled() => return Err(AppError::cancelled()),
+            response = request.send() => response?,
+        };
+        // 416 on a resume means the previous run finished the transfer but not the move into the store.
+        let written = if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            offset
+        } else {
+            let mut response = response.error_for_status()?;
+            let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+            let mut written = if resumed { offset } else { 0 };
+            if response.content_length().is_some_and(|len| written + len > self.max_bytes) {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Ok(None);
+            }
+
+            let mut file = tokio::fs::OpenOptions::new().create(true).write(true).append(resumed).truncate(!resumed).open(&partial).await?;
+            loop {
+                let chunk = tokio::select! {
+                    _ = cancel.cancelled() => {
+                        // What arrived so far stays in the partial file for the next attempt.
+                        file.flush().await?;
+                        return Err(AppError::cancelled());
+                    }
+                    chunk = response.chunk() => chunk?,
+                };
+                let Some(chunk) = chunk else { break; };
+                written += chunk.len() as u64;
+                if written > self.max_bytes {
+                    drop(file);
+                    let _ = tokio::fs::remove_file(&partial).await;
+                    return Ok(None);
+                }
+                file.write_all(&chunk).await?;
+            }
+            file.sync_data().await?;
+            written
+        };
+
+        let sha256 = {
+            let partial = partial.clone();
+            tauri::async_runtime::spawn_blocking(move || Self::hash_file(&partial)).await??
+        };
+        let object = self.root.join("objects").join(&sha256);
+        if tokio::fs::try_exists(&object).await? {
+            tokio::fs::remove_file(&partial).await?;
+        } else {
+            tokio::fs::rename(&partial, &object).await?;
+        }
+        Ok(Some((sha256, written)))
+    }
+
+    /// Stable per-file key: SHA-256 of the URL without its (expiring) query string.
+    fn key(url: &str) -> Result<String, AppError> {
+        let mut parsed = url::Url::parse(url)?;
+        parsed.set_query(None);
+        Ok(hex(ring::digest::digest(&SHA256, parsed.as_str().as_bytes()).as_ref()))
+    }
+
+    fn hash_file(path: &Path) -> Result<String, AppError> {
+        let mut file = std::fs::File::open(path)?;
+        let mut context = Context::new(&SHA256);
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buffer)?;
+            if n == 0 { break; }
+            context.update(&buffer[..n]);
+        }
+        Ok(hex(context.finish().as_ref()))
+    }
+
+    async fn record(&self, entries: &[ManifestEntry]) -> Result<(), AppError> {
+        if entries.is_empty() { return Ok(()); }
+        let mut lines = Vec::new();
+        for entry in entries {
+            lines.extend(serde_json::to_vec(entry)?);
+            lines.push(b'\n');
+        }
+        let _guard = self.manifest.lock().await;
+        let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(self.root.join("manifest.jsonl")).await?;
+        file.write_all(&lines).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::sync::mpsc;
+    use crate::core::op_manager::OperationManager;
+
+    /// Serves `body` over plain HTTP, honouring `Range: bytes=<n>-`, and reports the offset of each request.
+    async fn serve(body: Vec<u8>) -> (String, mpsc::UnboundedReceiver<Option<usize>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buffer).await.unwrap_or(0);
+                    if n == 0 { break; }
+                    request.extend_from_slice(&buffer[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let offset = request.lines()
+                    .find_map(|l| l.strip_prefix("range: bytes="))
+                    .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+                let _ = tx.send(offset);
+                let head = match offset {
+                    Some(o) => format!("HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n", body.len() - o, o, body.len() - 1, body.len()),
+                    None => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len()),
+                };
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body[offset.unwrap_or(0)..]).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        (format!("http://{}/attachments/1/2/file.bin?ex=1&hm=sig", addr), rx)
+    }
+
+    #[test]
+    fn media_urls_collect_attachments_and_https_embeds() {
+        let message = serde_json::json!({
+            "attachments": [{ "url": "https://cdn.example/a.png", "size": 10 }, { "url": "https://cdn.example/b.txt" }],
+            "embeds": [{
+                "image": { "url": "https://cdn.example/a.png" },
+                "thumbnail": { "url": "http://insecure.example/t.png" },
+                "video": { "url": "https://media.example/v.mp4" },
+            }],
+        });
+        assert_eq!(AttachmentStore::media_urls(&message), vec![
+            ("https://cdn.example/a.png".to_string(), Some(10)),
+            ("https://cdn.example/b.txt".to_string(), None),
+            ("https://media.example/v.mp4".to_string(), None),
+        ]);
+        assert!(AttachmentStore::media_urls(&serde_json::json!({ "content": "text only" })).is_empty());
+    }
+
+    #[test]
+    fn keys_ignore_the_signed_query() {
+        let key = AttachmentStore::key("https://cdn.example/attachments/1/2/a.png?ex=1&hm=x").unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, AttachmentStore::key("https://cdn.example/attachments/1/2/a.png?ex=2&hm=y").unwrap());
+        assert_ne!(key, AttachmentStore::key("https://cdn.example/attachments/1/2/b.png?ex=1&hm=x").unwrap());
+    }
+
+    #[tokio::test]
+    async fn partial_downloads_resume_with_a_range_request() {
+        let body: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let (url, mut requests) = serve(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::open_at(dir.path().to_path_buf(), None).unwrap();
+        let partial = dir.path().join("partial").join(format!("{}.part", AttachmentStore::key(&url).unwrap()));
+        std::fs::write(&partial, &body[..400]).unwrap();
+
+        let (sha256, size) = store.download(&url, &CancellationToken::new()).await.unwrap().unwrap();
+        assert_eq!(requests.recv().await.unwrap(), Some(400));
+        assert_eq!(size, 1000);
+        assert_eq!(sha256, hex(ring::digest::digest(&SHA256, &body).as_ref()));
+        assert_eq!(std::fs::read(dir.path().join("objects").join(&sha256)).unwrap(), body);
+        assert!(!partial.exists());
+    }
+
+    #[tokio::test]
+    async fn the_size_cap_and_aborts_stop_downloads() {
+        let (url, mut requests) = serve(vec![7; 100]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = AttachmentStore::open_at(dir.path().to_path_buf(), Some(50)).unwrap();
+
+        // Too large by Content-Length: nothing is kept.
+        assert_eq!(store.download(&url, &CancellationToken::new()).await.unwrap(), None);
+        assert_eq!(requests.recv().await.unwrap(), None);
+        assert_eq!(std::fs::read_dir(dir.path().join("partial")).unwrap().count(), 0);
+
+        // Too large by the size Discord reports: skipped without a request.
+        let manager = OperationManager::new();
+        let op = manager.claim("purge", None, true).unwrap();
+        let message = serde_json::json!({ "id": "9", "attachments": [{ "url": url, "size": 51 }] });
+        assert_eq!(store.archive_message(&op, "1", &message).await.unwrap(), 0);
+        assert!(requests.try_recv().is_err());
+        let manifest = std::fs::read_to_string(dir.path().join("manifest.jsonl")).unwrap();
+        assert!(manifest.contains("\"skipped_too_large\""));
+
+        op.abort();
+        assert!(store.download(&url, &op.cancel).await.unwrap_err().is_cancelled());
+        assert!(store.archive_message(&op, "1", &message).await.unwrap_err().is_cancelled());
+    }
+}
@@ -0,0 +1,137 @@
+// src-tauri/src/core/content_filter.rs
+
+use regex::{Regex, RegexBuilder};
+use serde::{Serialize, Deserialize};
+use crate::core::error::AppError;
+
+/// Compiled patterns are capped so a pathological expression cannot stall a job.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Content condition of a purge job, as sent by the UI and stored in the job journal.
+///
+/// Text terms are case-insensitive; regexes are too unless `case_sensitive` is set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterExpr {
+    /// The text appears anywhere in the message.
+    Contains { value: String },
+    /// The text appears as a whole word (or phrase) bounded by non-word characters.
+    Word { value: String },
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        case_sensitive: bool,
+    },
+    /// Every term matches. An empty list matches everything.
+    All { terms: Vec<FilterExpr> },
+    /// At least one term matches. An empty list matches nothing.
+    Any { terms: Vec<FilterExpr> },
+    Not { term: Box<FilterExpr> },
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Contains(String),
+    Regex(Regex),
+    All(Vec<Matcher>),
+    Any(Vec<Matcher>),
+    Not(Box<Matcher>),
+}
+
+impl Matcher {
+    fn compile(expr: &FilterExpr) -> Result<Self, AppError> {
+        Ok(match expr {
+            FilterExpr::Contains { value } => Self::Contains(value.to_lowercase()),
+            FilterExpr::Word { value } => Self::Regex(build_regex(&format!(r"(?:^|\W){}(?:\W|$)", regex::escape(value.trim())), false)?),
+            FilterExpr::Regex { pattern, case_sensitive } => Self::Regex(build_regex(pattern, *case_sensitive)?),
+            FilterExpr::All { terms } => Self::All(terms.iter().map(Self::compile).collect::<Result<_, _>>()?),
+            FilterExpr::Any { terms } => Self::Any(terms.iter().map(Self::compile).collect::<Result<_, _>>()?),
+            FilterExpr::Not { term } => Self::Not(Box::new(Self::compile(term)?)),
+        })
+    }
+
+    /// `lowered` is `content` in lowercase, computed once per message for the substring terms.
+    fn matches(&self, content: &str, lowered: &str) -> bool {
+        match self {
+            Self::Contains(value) => lowered.contains(value.as_str()),
+            Self::Regex(regex) => regex.is_match(content),
+            Self::All(terms) => terms.iter().all(|t| t.matches(content, lowered)),
+            Self::Any(terms) => terms.iter().any(|t| t.matches(content, lowered)),
+            Self::Not(term) => !term.matches(content, lowered),
+        }
+    }
+}
+
+fn build_regex(pattern: &str, case_sensitive: bool) -> Result<Regex, AppError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| AppError {
+            user_message: format!("Invalid content filter pattern: {}", pattern),
+            error_code: "invalid_filter_pattern".into(),
+            technical_details: Some(e.to_string()),
+        })
+}
+
+/// Content filters of a job compiled once: the legacy `search_query` substring, the filter
+/// expression and the exclusion list. A message is eligible if it matches both inclusion
+/// conditions (when set) and none of the exclusions.
+#[derive(Debug, Default)]
+pub struct ContentFilter {
+    include: Vec<Matcher>,
+    exclude: Vec<Matcher>,
+}
+
+impl ContentFilter {
+    pub fn compile(search_query: Option<&str>, expression: Option<&FilterExpr>, exclusions: &[FilterExpr]) -> Result<Self, AppError> {
+        let mut include = Vec::new();
+        if let Some(query) = search_query {
+            include.push(Matcher::Contains(query.to_lowercase()));
+        }
+        if let Some(expression) = expression {
+            include.push(Matcher::compile(expression)?);
+        }
+        Ok(Self {
+            include,
+            exclude: exclusions.iter().map(Matcher::compile).collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn matches(&self, content: &str) -> bool {
+        if self.include.is_empty() && self.exclude.is_empty() { return true; }
+        let lowered = content.to_lowercase();
+        self.include.iter().all(|m| m.matches(content, &lowered)) && !self.exclude.iter().any(|m| m.matches(content, &lowered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: &str) -> FilterExpr { FilterExpr::Word { value: value.into() } }
+
+    #[test]
+    fn expressions_combine_and_exclusions_win() {
+        let expr = FilterExpr::All { terms: vec![
+            FilterExpr::Any { terms: vec![word("cat"), FilterExpr::Regex { pattern: r"\bdogs?\b".into(), case_sensitive: false }] },
+            FilterExpr::Not { term: Box::new(FilterExpr::Contains { value: "draft".into() }) },
+        ]};
+        let filter = ContentFilter::compile(None, Some(&expr), &[word("keep")]).unwrap();
+        assert!(filter.matches("My CAT is here"));
+        assert!(filter.matches("two Dogs"));
+        assert!(!filter.matches("concatenate"));
+        assert!(!filter.matches("cat DRAFT"));
+        assert!(!filter.matches("cat, but keep this one"));
+
+        let legacy = ContentFilter::compile(Some("Hello"), None, &[]).unwrap();
+        assert!(legacy.matches("say hello there") && !legacy.matches("bye"));
+        assert!(ContentFilter::default().matches("anything"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let err = ContentFilter::compile(None, Some(&FilterExpr::Regex { pattern: "(unclosed".into(), case_sensitive: true }), &[]).unwrap_err();
+        assert_eq!(err.error_code, "invalid_filter_pattern");
+    }
+}
@@ -0,0 +1,339 @@
+// src-tauri/src/core/data_package.rs
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use crate::core::error::AppError;
+use crate::core::snowflake;
+
+/// A message we authored, as listed in the package. Only ids are kept; the content stays in the zip.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageMessage {
+    pub id: String,
+    /// Unix milliseconds, derived from the snowflake.
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PackageChannel {
+    pub channel_id: String,
+    pub channel_type: Option<u8>,
+    pub name: Option<String>,
+    pub guild_id: Option<String>,
+    pub guild_name: Option<String>,
+    pub messages: Vec<PackageMessage>,
+}
+
+/// Per-channel overview returned to the UI (message lists omitted).
+#[derive(Debug, Serialize, Clone)]
+pub struct PackageChannelSummary {
+    pub channel_id: String,
+    pub channel_type: Option<u8>,
+    pub name: Option<String>,
+    pub guild_id: Option<String>,
+    pub guild_name: Option<String>,
+    pub message_count: usize,
+    pub first_message_at: Option<u64>,
+    pub last_message_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PackageSummary {
+    pub source: String,
+    pub imported_at: i64,
+    pub channel_count: usize,
+    pub guild_count: usize,
+    pub message_count: usize,
+    pub channels: Vec<PackageChannelSummary>,
+}
+
+/// Index of Discord's "Request My Data" package (`package.zip`), stored as
+/// `<app_local_data_dir>/data_package/index.json` once imported.
+///
+/// # Logic
+/// Reads `messages/c<id>/channel.json` and `messages/c<id>/messages.json` (or the `messages.csv` of
+/// older packages, where the folder has no `c` prefix), falling back to `messages/index.json` and
+/// `servers/index.json` for names. Entries that do not parse are skipped rather than failing the
+/// import. This reaches channels `fetch_channels` can no longer list: guilds we left and DMs
+/// closed long ago.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageIndex {
+    pub source: String,
+    pub imported_at: i64,
+    pub channels: Vec<PackageChannel>,
+}
+
+impl PackageIndex {
+    const DIR_NAME: &'static str = "data_package";
+    const FILE_NAME: &'static str = "index.json";
+
+    /// Parses `package.zip`. This is blocking I/O; call it from a blocking task.
+    pub fn from_zip(path: &Path) -> Result<Self, AppError> {
+        Self::from_archive(std::fs::File::open(path)?, path.to_string_lossy().into_owned())
+    }
+
+    fn from_archive(reader: impl Read + Seek, source: String) -> Result<Self, AppError> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        let mut channels: HashMap<String, PackageChannel> = HashMap::new();
+        let mut channel_names: HashMap<String, String> = HashMap::new();
+        let mut guild_names: HashMap<String, String> = HashMap::new();
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let name = entry.name().to_string();
+            let parts: Vec<&str> = name.split('/').collect();
+
+            match parts.as_slice() {
+                ["messages", "index.json"] => channel_names = read_text(&mut entry).ok().and_then(|text| name_index(&text).ok()).unwrap_or_default(),
+                ["servers", "index.json"] => guild_names = read_text(&mut entry).ok().and_then(|text| name_index(&text).ok()).unwrap_or_default(),
+                ["messages", dir, file] => {
+                    let channel_id = dir.strip_prefix('c').unwrap_or(*dir);
+                    if channel_id.is_empty() || !channel_id.bytes().all(|b| b.is_ascii_digit()) { continue; }
+                    match *file {
+                        "channel.json" => {
+                            let Some(meta) = read_text(&mut entry).ok().and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok()) else { continue; };
+                            let channel = channel_entry(&mut channels, channel_id);
+                            channel.channel_type = meta["type"].as_u64().map(|t| t as u8)
+                                .or_else(|| channel_type_from_name(meta["type"].as_str()?));
+                            channel.name = meta["name"].as_str().map(|s| s.to_string());
+                            channel.guild_id = json_id(&meta["guild"]["id"]);
+                            channel.guild_name = meta["guild"]["name"].as_str().map(|s| s.to_string());
+                        }
+                        "messages.json" => {
+                            let Some(rows) = read_text(&mut entry).ok().and_then(|text| serde_json::from_str::<Vec<serde_json::Value>>(&text).ok()) else { continue; };
+                            channel_entry(&mut channels, channel_id).messages
+                                .extend(rows.iter().filter_map(|row| json_id(&row["ID"])).filter_map(package_message));
+                        }
+                        "messages.csv" => {
+                            let Ok(text) = read_text(&mut entry) else { continue; };
+                            let ids = csv_first_column(&text);
+                            channel_entry(&mut channels, channel_id).messages.extend(ids.into_iter().filter_map(package_message));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut channels: Vec<PackageChannel> = channels.into_values().map(|mut c| {
+            if c.name.is_none() {
+                c.name = channel_names.get(&c.channel_id).cloned();
+            }
+            if c.guild_name.is_none() {
+                c.guild_name = c.guild_id.as_ref().and_then(|g| guild_names.get(g)).cloned();
+            }
+            c.messages.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+            c.messages.dedup_by(|a, b| a.id == b.id);
+            c
+        }).collect();
+        channels.sort_by_key(|c| std::cmp::Reverse(c.messages.len()));
+
+        Ok(Self {
+            source,
+            imported_at: chrono::Utc::now().timestamp_millis(),
+            channels,
+        })
+    }
+
+    fn path(app: &AppHandle) -> Result<PathBuf, AppError> {
+        let dir = app.path().app_local_data_dir()?.join(Self::DIR_NAME);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir.join(Self::FILE_NAME))
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), AppError> {
+        let path = Self::path(app)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn load(app: &AppHandle) -> Result<Self, AppError> {
+        let data = std::fs::read(Self::path(app)?).map_err(|e| AppError {
+            user_message: "No data package has been imported yet.".into(),
+            error_code: "package_not_imported".into(),
+            technical_details: Some(e.to_string()),
+        })?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    pub fn channel(&self, channel_id: &str) -> Option<&PackageChannel> {
+        self.channels.iter().find(|c| c.channel_id == channel_id)
+    }
+
+    pub fn summary(&self) -> PackageSummary {
+        let mut guilds: Vec<&str> = self.channels.iter().filter_map(|c| c.guild_id.as_deref()).collect();
+        guilds.sort_unstable();
+        guilds.dedup();
+        PackageSummary {
+            source: self.source.clone(),
+            imported_at: self.imported_at,
+            channel_count: self.channels.len(),
+            guild_count: guilds.len(),
+            message_count: self.channels.iter().map(|c| c.messages.len()).sum(),
+            channels: self.channels.iter().map(|c| PackageChannelSummary {
+                channel_id: c.channel_id.clone(),
+                channel_type: c.channel_type,
+                name: c.name.clone(),
+                guild_id: c.guild_id.clone(),
+                guild_name: c.guild_name.clone(),
+                message_count: c.messages.len(),
+                first_message_at: c.messages.last().map(|m| m.timestamp),
+                last_message_at: c.messages.first().map(|m| m.timestamp),
+            }).collect(),
+        }
+    }
+}
+
+fn channel_entry<'a>(channels: &'a mut HashMap<String, PackageChannel>, channel_id: &str) -> &'a mut PackageChannel {
+    channels.entry(channel_id.to_string()).or_insert_with(|| PackageChannel {
+        channel_id: channel_id.to_string(),
+        ..Default::default()
+    })
+}
+
+fn read_text(entry: &mut impl Read) -> Result<String, AppError> {
+    let mut text = String::new();
+    entry.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// `{ "<id>": "<name>" }` lookup files; entries without a name are `null` and skipped.
+fn name_index(text: &str) -> Result<HashMap<String, String>, AppError> {
+    let raw: HashMap<String, serde_json::Value> = serde_json::from_str(text)?;
+    Ok(raw.into_iter().filter_map(|(id, name)| Some((id, name.as_str()?.to_string()))).collect())
+}
+
+/// Ids appear as JSON numbers in some package versions and as strings in others.
+fn json_id(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => n.as_u64().map(|n| n.to_string()),
+        _ => None,
+    }
+}
+
+fn package_message(id: String) -> Option<PackageMessage> {
+    let timestamp = snowflake::timestamp_ms(snowflake::parse(&id)?);
+    Some(PackageMessage { id, timestamp })
+}
+
+/// Newer packages spell the channel type out (`"DM"`, `"GUILD_TEXT"`, ...).
+fn channel_type_from_name(name: &str) -> Option<u8> {
+    Some(match name {
+        "GUILD_TEXT" => 0,
+        "DM" => 1,
+        "GUILD_VOICE" => 2,
+        "GROUP_DM" => 3,
+        "GUILD_ANNOUNCEMENT" | "GUILD_NEWS" => 5,
+        "ANNOUNCEMENT_THREAD" | "GUILD_NEWS_THREAD" => 10,
+        "PUBLIC_THREAD" | "GUILD_PUBLIC_THREAD" => 11,
+        "PRIVATE_THREAD" | "GUILD_PRIVATE_THREAD" => 12,
+        "GUILD_STAGE_VOICE" => 13,
+        "GUILD_FORUM" => 15,
+        _ => return None,
+    })
+}
+
+/// First column of every data row of `messages.csv` (`ID,Timestamp,Contents,Attachments`).
+/// Quoted fields may span lines, so the text is scanned rather than split on newlines.
+fn csv_first_column(text: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut in_quotes = false;
+    let mut at_row_start = true;
+    let mut field = String::new();
+    let mut capturing = true;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if at_row_start {
+            at_row_start = false;
+            capturing = true;
+            field.clear();
+        }
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => { chars.next(); }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                if capturing { ids.push(std::mem::take(&mut field)); }
+                capturing = false;
+            }
+            '\n' if !in_quotes => {
+                if capturing && !field.trim().is_empty() { ids.push(field.trim().to_string()); }
+                at_row_start = true;
+            }
+            _ if capturing => field.push(c),
+            _ => {}
+        }
+    }
+    if capturing && !at_row_start && !field.trim().is_empty() {
+        ids.push(field.trim().to_string());
+    }
+    // The header row yields "ID", which is dropped by the digit check.
+    ids.retain(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()));
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_ids_survive_multiline_quoted_content() {
+        let csv = "ID,Timestamp,Contents,Attachments\n\
+                   1100000000000000001,2023-05-01 10:00:00,\"hello, \"\"world\"\"\nsecond line\",\n\
+                   1100000000000000002,2023-05-01 10:01:00,plain,https://cdn.discordapp.com/a.png\r\n";
+        assert_eq!(csv_first_column(csv), vec!["1100000000000000001", "1100000000000000002"]);
+    }
+
+    fn package(files: &[(&str, &[u8])]) -> std::io::Cursor<Vec<u8>> {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    #[test]
+    fn packages_are_indexed_and_malformed_entries_skipped() {
+        let zip = package(&[
+            ("messages/index.json", r#"{ "111": "general", "222": null, "333": "Direct Message with bob" }"#.as_bytes()),
+            ("servers/index.json", r#"{ "900": "Guild" }"#.as_bytes()),
+            ("messages/c111/channel.json", r#"{ "id": "111", "type": 0, "guild": { "id": 900 } }"#.as_bytes()),
+            ("messages/c111/messages.json", r#"[{ "ID": 1100000000000000001 }, { "ID": "1100000000000000002" }, { "ID": "bogus" }, { "Contents": "no id" }]"#.as_bytes()),
+            ("messages/c333/channel.json", r#"{ "id": "333", "type": "DM" }"#.as_bytes()),
+            ("messages/444/messages.csv", "ID,Timestamp,Contents,Attachments\n1100000000000000003,2023-05-01 10:00:00,hi,\n".as_bytes()),
+            ("messages/c555/channel.json", "{ not json".as_bytes()),
+            ("messages/c666/messages.json", "[oops".as_bytes()),
+            ("messages/cabc/messages.json", r#"[{ "ID": "1100000000000000004" }]"#.as_bytes()),
+            ("messages/c111/attachments/a.png", &b"binary"[..]),
+            // Not UTF-8: unreadable as text, skipped like any other malformed entry.
+            ("messages/c777/channel.json", &b"{ \"name\": \"\xff\xfe\" }"[..]),
+            ("messages/c777/messages.csv", &b"ID\n\xff1100000000000000005\n"[..]),
+        ]);
+        let index = PackageIndex::from_archive(zip, "package.zip".into()).unwrap();
+
+        let ids: Vec<&str> = index.channels.iter().map(|c| c.channel_id.as_str()).collect();
+        assert_eq!(ids, ["111", "444", "333"]);
+
+        let guild_channel = index.channel("111").unwrap();
+        assert_eq!((guild_channel.channel_type, guild_channel.name.as_deref()), (Some(0), Some("general")));
+        assert_eq!((guild_channel.guild_id.as_deref(), guild_channel.guild_name.as_deref()), (Some("900"), Some("Guild")));
+        let message_ids: Vec<&str> = guild_channel.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(message_ids, ["1100000000000000002", "1100000000000000001"]);
+
+        let dm = index.channel("333").unwrap();
+        assert_eq!((dm.channel_type, dm.name.as_deref(), dm.messages.len()), (Some(1), Some("Direct Message with bob"), 0));
+        assert_eq!(index.channel("444").unwrap().messages[0].id, "1100000000000000003");
+
+        let summary = index.summary();
+        assert_eq!((summary.channel_count, summary.guild_count, summary.message_count), (3, 1, 3));
+    }
+}
@@ -15,6 +15,21 @@ impl std::fmt::Display for AppError {
 
 impl std::error::Error for AppError {}
 
+impl AppError {
+    /// Error for requests abandoned because their operation was aborted.
+    pub fn cancelled() -> Self {
+        Self {
+            user_message: "Request cancelled.".into(),
+            error_code: "cancelled".into(),
+            technical_details: None,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.error_code == "cancelled"
+    }
+}
+
 impl Default for AppError {
     fn default() -> Self {
         Self {
@@ -115,6 +130,16 @@ impl From<tokio::time::error::Elapsed> for AppError {
     }
 }
 
+impl From<zip::result::ZipError> for AppError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self {
+            user_message: "Failed to read the data package archive.".into(),
+            error_code: "package_error".into(),
+            technical_details: Some(e.to_string()),
+        }
+    }
+}
+
 impl From<serde_json::Error> for AppError {
     fn from(e: serde_json::Error) -> Self {
         Self {
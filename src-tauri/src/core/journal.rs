@@ -0,0 +1,312 @@
+// src-tauri/src/core/journal.rs
+
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use crate::core::content_filter::{ContentFilter, FilterExpr};
+use crate::core::error::AppError;
+use crate::core::op_manager::{OperationGuard, OperationManager};
+
+/// Message selection parameters for a purge job.
+/// Stored verbatim in the journal so a resumed job evaluates messages exactly as the original run did.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PurgeFilters {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub search_query: Option<String>,
+    pub purge_reactions: bool,
+    pub simulation: bool,
+    pub only_attachments: bool,
+    /// Use the message search index (our own messages only) instead of walking the full channel history.
+    pub use_search: bool,
+    /// Also delete other members' messages in guilds where we hold Manage Messages.
+    pub include_manageable: bool,
+    /// Append every message to the local archive before it is deleted.
+    pub archive: bool,
+    /// Download attachment and embed media of deleted messages into the attachment store.
+    pub download_attachments: bool,
+    /// Per-file size cap for downloads; defaults to `AttachmentStore::DEFAULT_MAX_BYTES`.
+    pub attachment_max_bytes: Option<u64>,
+    /// Content condition on top of `search_query`: terms, whole words and regexes combined with AND/OR/NOT.
+    pub content_filter: Option<FilterExpr>,
+    /// Messages matching any of these are never deleted.
+    pub exclusions: Vec<FilterExpr>,
+    /// Never delete pinned messages.
+    pub keep_pinned: bool,
+    /// Never delete messages that someone other than us has reacted to.
+    pub keep_reacted_by_others: bool,
+    /// Never delete messages with attachments.
+    pub keep_attachments: bool,
+}
+
+impl PurgeFilters {
+    /// Compiles the content conditions, rejecting invalid patterns before any message is touched.
+    pub fn compile_content_filter(&self) -> Result<ContentFilter, AppError> {
+        ContentFilter::compile(self.search_query.as_deref(), self.content_filter.as_ref(), &self.exclusions)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job is executing, or the process died while it was executing.
+    Running,
+    /// The user aborted the job; it can be resumed later.
+    Aborted,
+    /// The job stopped on an error (network, expired token, ...); it can be resumed later.
+    Interrupted,
+    /// Every channel has been fully processed.
+    Completed,
+}
+
+/// A failed API action on a single message, kept for the job report.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActionError {
+    pub message_id: String,
+    /// `delete` or `reaction`.
+    pub action: String,
+    /// HTTP status, or `None` if the request never got a response.
+    pub status: Option<u16>,
+    pub detail: Option<String>,
+}
+
+/// Per-channel cursor state. `before` is the id of the last message that was fully processed,
+/// which is exactly the `before` query parameter needed to fetch the next unprocessed page.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChannelProgress {
+    pub channel_id: String,
+    pub before: Option<String>,
+    pub completed: bool,
+    pub scanned: u64,
+    pub deleted: u64,
+    #[serde(default)]
+    pub skipped: u64,
+    #[serde(default)]
+    pub forbidden: u64,
+    #[serde(default)]
+    pub archived: u64,
+    /// Messages that passed every filter, whether or not we were allowed to act on them.
+    #[serde(default)]
+    pub matched: u64,
+    /// Deletes answered with 404: the message was already gone.
+    #[serde(default)]
+    pub not_found: u64,
+    #[serde(default)]
+    pub failed: u64,
+    #[serde(default)]
+    pub reactions_removed: u64,
+    /// 429 responses the rate limiter had to wait out for this channel's requests.
+    #[serde(default)]
+    pub rate_limited_retries: u64,
+    /// The first `MAX_RECORDED_ERRORS` failures, in order.
+    #[serde(default)]
+    pub errors: Vec<ActionError>,
+}
+
+impl ChannelProgress {
+    const MAX_RECORDED_ERRORS: usize = 200;
+
+    /// Records a failure, keeping the journal bounded on channels where everything fails.
+    pub fn record_error(&mut self, error: ActionError) {
+        if self.errors.len() < Self::MAX_RECORDED_ERRORS {
+            self.errors.push(error);
+        }
+    }
+}
+
+/// End-of-job summary, emitted as `job_report` and written to `jobs/<job_id>.report.json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobReport {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub simulation: bool,
+    pub created_at: i64,
+    pub finished_at: i64,
+    pub scanned: u64,
+    pub matched: u64,
+    pub deleted: u64,
+    pub forbidden: u64,
+    pub not_found: u64,
+    pub failed: u64,
+    pub rate_limited_retries: u64,
+    pub channels: Vec<ChannelProgress>,
+}
+
+/// Durable record of a bulk purge job, written to `<app_local_data_dir>/jobs/<job_id>.json`.
+///
+/// # Logic
+/// The journal is rewritten atomically (temp file + rename) after every destructive action
+/// and at every page boundary, so a crash loses at most the message that was in flight.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobJournal {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub filters: PurgeFilters,
+    pub channels: Vec<ChannelProgress>,
+    pub deleted_total: u64,
+    /// DMs reopened for this job that are closed again once it completes.
+    #[serde(default)]
+    pub reopened_dms: Vec<String>,
+}
+
+impl JobJournal {
+    const DIR_NAME: &'static str = "jobs";
+
+    pub fn new(channel_ids: Vec<String>, filters: PurgeFilters) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        Self {
+            job_id: uuid::Uuid::new_v4().to_string(),
+            status: JobStatus::Running,
+            created_at: now,
+            updated_at: now,
+            filters,
+            channels: channel_ids.into_iter().map(|channel_id| ChannelProgress {
+                channel_id,
+                before: None,
+                completed: false,
+                scanned: 0,
+                deleted: 0,
+                skipped: 0,
+                forbidden: 0,
+                archived: 0,
+                matched: 0,
+                not_found: 0,
+                failed: 0,
+                reactions_removed: 0,
+                rate_limited_retries: 0,
+                errors: Vec::new(),
+            }).collect(),
+            deleted_total: 0,
+            reopened_dms: Vec::new(),
+        }
+    }
+
+    /// Resolves (and creates if needed) the directory holding all job journals.
+    pub fn dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+        let dir = app.path().app_local_data_dir()?.join(Self::DIR_NAME);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn path(app: &AppHandle, job_id: &str) -> Result<PathBuf, AppError> {
+        Self::path_in(&Self::dir(app)?, job_id)
+    }
+
+    fn path_in(dir: &Path, job_id: &str) -> Result<PathBuf, AppError> {
+        // Job ids are always UUIDs; rejecting anything else keeps ids from escaping the jobs directory.
+        uuid::Uuid::parse_str(job_id).map_err(|_| AppError {
+            user_message: "Invalid job identifier.".into(),
+            error_code: "invalid_job_id".into(),
+            technical_details: Some(job_id.to_string()),
+        })?;
+        Ok(dir.join(format!("{}.json", job_id)))
+    }
+
+    /// Claims the run slot for this job, then writes the journal as running. A rejected claim
+    /// returns before anything is written, so a job that never started leaves no journal behind.
+    pub fn claim(&mut self, op_manager: &OperationManager, dir: &Path) -> Result<OperationGuard, AppError> {
+        let guard = op_manager.claim("purge", Some(self.job_id.clone()), !self.filters.simulation)?;
+        self.status = JobStatus::Running;
+        self.save_in(dir)?;
+        Ok(guard)
+    }
+
+    /// Persists the journal, replacing the previous snapshot atomically.
+    pub fn save(&mut self, app: &AppHandle) -> Result<(), AppError> {
+        self.save_in(&Self::dir(app)?)
+    }
+
+    fn save_in(&mut self, dir: &Path) -> Result<(), AppError> {
+        self.updated_at = chrono::Utc::now().timestamp_millis();
+        let path = Self::path_in(dir, &self.job_id)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Builds the job report from the current counters.
+    pub fn report(&self) -> JobReport {
+        let sum = |f: fn(&ChannelProgress) -> u64| self.channels.iter().map(f).sum();
+        JobReport {
+            job_id: self.job_id.clone(),
+            status: self.status,
+            simulation: self.filters.simulation,
+            created_at: self.created_at,
+            finished_at: self.updated_at,
+            scanned: sum(|c| c.scanned),
+            matched: sum(|c| c.matched),
+            deleted: self.deleted_total,
+            forbidden: sum(|c| c.forbidden),
+            not_found: sum(|c| c.not_found),
+            failed: sum(|c| c.failed),
+            rate_limited_retries: sum(|c| c.rate_limited_retries),
+            channels: self.channels.clone(),
+        }
+    }
+
+    /// Writes the report next to the journal, replacing the report of any earlier run of the job.
+    pub fn save_report(&self, app: &AppHandle) -> Result<JobReport, AppError> {
+        let report = self.report();
+        let path = Self::path(app, &self.job_id)?.with_extension("report.json");
+        std::fs::write(&path, serde_json::to_vec_pretty(&report)?)?;
+        Ok(report)
+    }
+
+    pub fn load_report(app: &AppHandle, job_id: &str) -> Result<JobReport, AppError> {
+        let path = Self::path(app, job_id)?.with_extension("report.json");
+        let data = std::fs::read(&path).map_err(|e| AppError {
+            user_message: "No report has been written for this job yet.".into(),
+            error_code: "report_not_found".into(),
+            technical_details: Some(e.to_string()),
+        })?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    pub fn load(app: &AppHandle, job_id: &str) -> Result<Self, AppError> {
+        let path = Self::path(app, job_id)?;
+        let data = std::fs::read(&path).map_err(|e| AppError {
+            user_message: "Job journal not found.".into(),
+            error_code: "job_not_found".into(),
+            technical_details: Some(e.to_string()),
+        })?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Lists every journal on disk, newest first. Unreadable files are skipped.
+    pub fn list(app: &AppHandle) -> Result<Vec<Self>, AppError> {
+        let mut journals: Vec<Self> = std::fs::read_dir(Self::dir(app)?)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter(|path| !path.to_string_lossy().ends_with(".report.json"))
+            .filter_map(|path| std::fs::read(path).ok())
+            .filter_map(|data| serde_json::from_slice(&data).ok())
+            .collect();
+        journals.sort_by_key(|j| std::cmp::Reverse(j.updated_at));
+        Ok(journals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_rejected_start_leaves_no_journal_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = OperationManager::new();
+        let running = manager.claim("leave_guilds", None, true).unwrap();
+
+        let mut journal = JobJournal::new(vec!["1".into()], PurgeFilters::default());
+        assert_eq!(journal.claim(&manager, dir.path()).err().unwrap().error_code, "operation_already_running");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        drop(running);
+        let _guard = journal.claim(&manager, dir.path()).unwrap();
+        assert!(dir.path().join(format!("{}.json", journal.job_id)).exists());
+    }
+}
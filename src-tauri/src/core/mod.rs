@@ -1,5 +1,16 @@
 // src-tauri/src/core/mod.rs
 pub mod error;
 pub mod vault;
+pub mod vault_backend;
+pub mod settings;
 pub mod op_manager;
-pub mod logger;
\ No newline at end of file
+pub mod logger;
+pub mod journal;
+pub mod snowflake;
+pub mod archive;
+pub mod attachments;
+pub mod data_package;
+pub mod plan;
+pub mod content_filter;
+pub mod schedule;
+pub mod policy;
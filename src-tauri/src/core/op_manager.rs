@@ -1,17 +1,35 @@
 // src-tauri/src/core/op_manager.rs
 
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use crate::core::error::AppError;
 
-/// Manages the runtime state of bulk operations (purges, departures, etc.).
-/// It provides thread-safe primitives for pausing, resuming, and aborting
-/// long-running asynchronous tasks across the Tauri command boundaries.
+type Registry = Arc<Mutex<HashMap<String, Arc<OperationState>>>>;
+
+/// Registry of the bulk operations (purges, departures, exports, etc.) currently executing.
+/// Each operation is keyed by its job id and owns its own pause/abort state, so several
+/// operations can run side by side and be controlled individually from the UI.
+///
+/// # Logic
+/// Only one *destructive* operation (anything that deletes, leaves or edits account data) may run
+/// at a time; read-only work such as exports and simulations can run alongside it. An operation
+/// is registered by `begin`, which hands out an `OperationGuard` that unregisters it when dropped,
+/// whether the command returns normally, bails out with `?`, or panics.
 pub struct OperationManager {
-    pub state: Arc<OperationState>,
+    jobs: Registry,
 }
 
 pub struct OperationState {
+    pub job_id: String,
+    /// Command that started the operation (`purge`, `leave_guilds`, `export`, ...).
+    pub kind: &'static str,
+    pub destructive: bool,
+    pub started_at: i64,
     /// True if an operation is actively executing its loop.
     pub is_running: AtomicBool,
     /// True if the user has requested a temporary pause.
@@ -20,17 +38,122 @@ pub struct OperationState {
     pub should_abort: AtomicBool,
     /// Notification handle used to wake up the worker thread when an operation is resumed.
     pub pause_notifier: Notify,
+    /// Cancelled together with `should_abort`, for waits that cannot poll the flag.
+    pub cancel: CancellationToken,
+    /// Units of work completed and planned; what a unit is depends on `kind`.
+    pub current: AtomicU64,
+    pub total: AtomicU64,
+}
+
+/// Snapshot of one registered operation, returned to the UI.
+#[derive(Debug, Serialize, Clone)]
+pub struct OperationStatus {
+    pub job_id: String,
+    pub kind: String,
+    pub destructive: bool,
+    pub started_at: i64,
+    pub is_running: bool,
+    pub is_paused: bool,
+    pub should_abort: bool,
+    pub current: u64,
+    pub total: u64,
 }
 
 impl OperationManager {
     pub fn new() -> Self {
-        Self {
-            state: Arc::new(OperationState {
-                is_running: AtomicBool::new(false),
-                is_paused: AtomicBool::new(false),
-                should_abort: AtomicBool::new(false),
-                pause_notifier: Notify::new(),
-            }),
+        Self { jobs: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Arc<OperationState>>> {
+        lock(&self.jobs)
+    }
+
+    /// Claims a run slot and announces the operation with a `job_started` event.
+    /// Operations without a journal pass `None` and get a fresh id.
+    pub fn begin(&self, app: &AppHandle, kind: &'static str, job_id: Option<String>, destructive: bool) -> Result<OperationGuard, AppError> {
+        let guard = self.claim(kind, job_id, destructive)?;
+        Self::announce(app, &guard);
+        Ok(guard)
+    }
+
+    /// Emits `job_started` for an operation registered through `claim`.
+    pub fn announce(app: &AppHandle, op: &OperationState) {
+        let _ = app.emit("job_started", serde_json::json!({ "job_id": op.job_id, "kind": op.kind, "destructive": op.destructive }));
+    }
+
+    /// Registers the operation unless it conflicts with one already running. The check and the
+    /// insertion happen under one lock, so two commands racing for the slot cannot both win.
+    pub fn claim(&self, kind: &'static str, job_id: Option<String>, destructive: bool) -> Result<OperationGuard, AppError> {
+        let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let mut jobs = self.jobs();
+        let conflict = jobs.get(&job_id).or_else(|| jobs.values().find(|j| destructive && j.destructive));
+        if let Some(running) = conflict {
+            return Err(AppError {
+                user_message: "Another operation is already running. Wait for it to finish or abort it first.".into(),
+                error_code: "operation_already_running".into(),
+                technical_details: Some(format!("{} ({})", running.job_id, running.kind)),
+            });
+        }
+
+        let state = Arc::new(OperationState {
+            job_id: job_id.clone(),
+            kind,
+            destructive,
+            started_at: chrono::Utc::now().timestamp_millis(),
+            is_running: AtomicBool::new(true),
+            is_paused: AtomicBool::new(false),
+            should_abort: AtomicBool::new(false),
+            pause_notifier: Notify::new(),
+            cancel: CancellationToken::new(),
+            current: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+        });
+        jobs.insert(job_id, state.clone());
+        Ok(OperationGuard { jobs: self.jobs.clone(), state })
+    }
+
+    pub fn get(&self, job_id: &str) -> Result<Arc<OperationState>, AppError> {
+        self.jobs().get(job_id).cloned().ok_or_else(|| AppError {
+            user_message: "No running operation with this id.".into(),
+            error_code: "operation_not_found".into(),
+            technical_details: Some(job_id.to_string()),
+        })
+    }
+
+    /// Every registered operation, oldest first.
+    pub fn list(&self) -> Vec<OperationStatus> {
+        let mut statuses: Vec<OperationStatus> = self.jobs().values().map(|s| s.status()).collect();
+        statuses.sort_by_key(|s| s.started_at);
+        statuses
+    }
+}
+
+/// A poisoned lock only means another task panicked mid-operation; the map itself stays usable.
+fn lock(jobs: &Registry) -> MutexGuard<'_, HashMap<String, Arc<OperationState>>> {
+    jobs.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Registration of a running operation. Dropping it marks the operation as stopped and frees its slot.
+pub struct OperationGuard {
+    jobs: Registry,
+    state: Arc<OperationState>,
+}
+
+impl std::ops::Deref for OperationGuard {
+    type Target = OperationState;
+
+    fn deref(&self) -> &OperationState {
+        &self.state
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        self.state.is_running.store(false, Ordering::SeqCst);
+        self.state.is_paused.store(false, Ordering::SeqCst);
+        let mut jobs = lock(&self.jobs);
+        if jobs.get(&self.state.job_id).is_some_and(|s| Arc::ptr_eq(s, &self.state)) {
+            jobs.remove(&self.state.job_id);
         }
     }
 }
@@ -39,15 +162,77 @@ impl OperationState {
     /// Blocks the current task if the `is_paused` flag is true.
     /// Used inside the bulk loops in `api/discord.rs`.
     pub async fn wait_if_paused(&self) {
-        while self.is_paused.load(Ordering::SeqCst) {
-            self.pause_notifier.notified().await;
+        loop {
+            // Created before the check so a `resume` landing in between still wakes us.
+            let resumed = self.pause_notifier.notified();
+            if !self.is_paused.load(Ordering::SeqCst) { return; }
+            resumed.await;
         }
     }
 
-    /// Resets all flags to their default state after an operation finishes or is aborted.
-    pub fn reset(&self) {
-        self.is_running.store(false, Ordering::SeqCst);
+    pub fn is_aborted(&self) -> bool {
+        self.should_abort.load(Ordering::SeqCst)
+    }
+
+    pub fn pause(&self) {
+        self.is_paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
         self.is_paused.store(false, Ordering::SeqCst);
-        self.should_abort.store(false, Ordering::SeqCst);
+        self.pause_notifier.notify_waiters();
+    }
+
+    /// Requests termination; a paused operation is woken so it can observe the flag.
+    pub fn abort(&self) {
+        self.should_abort.store(true, Ordering::SeqCst);
+        self.cancel.cancel();
+        self.resume();
+    }
+
+    pub fn set_progress(&self, current: u64, total: u64) {
+        self.current.store(current, Ordering::SeqCst);
+        self.total.store(total, Ordering::SeqCst);
+    }
+
+    pub fn status(&self) -> OperationStatus {
+        OperationStatus {
+            job_id: self.job_id.clone(),
+            kind: self.kind.to_string(),
+            destructive: self.destructive,
+            started_at: self.started_at,
+            is_running: self.is_running.load(Ordering::SeqCst),
+            is_paused: self.is_paused.load(Ordering::SeqCst),
+            should_abort: self.should_abort.load(Ordering::SeqCst),
+            current: self.current.load(Ordering::SeqCst),
+            total: self.total.load(Ordering::SeqCst),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destructive_operations_are_exclusive_and_slots_are_always_released() {
+        let manager = OperationManager::new();
+        let purge = manager.claim("purge", None, true).unwrap();
+        let err = manager.claim("leave_guilds", None, true).err().unwrap();
+        assert_eq!(err.error_code, "operation_already_running");
+        assert_eq!(manager.claim("purge", Some(purge.job_id.clone()), false).err().unwrap().error_code, "operation_already_running");
+
+        let export = manager.claim("export", None, false).unwrap();
+        assert_eq!(manager.list().len(), 2);
+        drop(purge);
+        drop(export);
+        assert!(manager.list().is_empty());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = manager.claim("purge", None, true).unwrap();
+            panic!("operation failed");
+        }));
+        assert!(result.is_err());
+        assert!(manager.claim("purge", None, true).is_ok());
     }
 }
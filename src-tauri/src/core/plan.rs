@@ -0,0 +1,301 @@
+// src-tauri/src/core/plan.rs
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use crate::core::content_filter::ContentFilter;
+use crate::core::error::AppError;
+use crate::core::journal::PurgeFilters;
+use crate::core::snowflake::{self, SnowflakeWindow};
+
+/// Characters of message content kept in a planned action for review.
+const SNIPPET_CHARS: usize = 120;
+
+/// What a purge does with one message. Real runs and simulations both act on this, so a plan
+/// lists exactly what the equivalent run would touch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageDecision {
+    /// The message passed the time, attachment and content filters.
+    pub matched: bool,
+    /// We may delete it: matched, and either ours or in a guild we manage.
+    pub delete: bool,
+    /// Matched, but authored by someone else where we cannot manage messages.
+    pub skipped_not_ours: bool,
+    /// URL-ready emoji parameters (`name` or `name:id`) of our reactions to remove.
+    pub reactions: Vec<String>,
+}
+
+/// Applies the job filters to one message.
+///
+/// # Logic
+/// Reactions are ours to remove whoever authored the message, so they only depend on the time
+/// window and the attachment filter; the content query, the keep rules and authorship apply to
+/// deletion alone.
+pub fn evaluate(message: &serde_json::Value, filters: &PurgeFilters, content_filter: &ContentFilter, window: &SnowflakeWindow, our_id: &str, can_manage: bool) -> MessageDecision {
+    let in_window = message["id"].as_str().and_then(snowflake::parse).is_some_and(|id| window.contains(id));
+    let has_attachments = message["attachments"].as_array().is_some_and(|arr| !arr.is_empty());
+    if !in_window || (filters.only_attachments && !has_attachments) {
+        return MessageDecision::default();
+    }
+
+    let kept = (filters.keep_pinned && message["pinned"].as_bool().unwrap_or(false))
+        || (filters.keep_attachments && has_attachments)
+        || (filters.keep_reacted_by_others && reacted_by_others(message));
+    let matched = !kept && content_filter.matches(message["content"].as_str().unwrap_or_default());
+    let is_ours = message["author"]["id"].as_str() == Some(our_id);
+    let reactions = if filters.purge_reactions {
+        message["reactions"].as_array().into_iter().flatten()
+            .filter(|r| r["me"].as_bool().unwrap_or(false))
+            .map(|r| {
+                let name = r["emoji"]["name"].as_str().unwrap_or("");
+                match r["emoji"]["id"].as_str() {
+                    Some(id) if !id.is_empty() => format!("{}:{}", name, id),
+                    _ => name.to_string(),
+                }
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    MessageDecision {
+        matched,
+        delete: matched && (is_ours || can_manage),
+        skipped_not_ours: matched && !is_ours && !can_manage,
+        reactions,
+    }
+}
+
+/// True if a reaction count exceeds our own contribution to it.
+fn reacted_by_others(message: &serde_json::Value) -> bool {
+    message["reactions"].as_array().into_iter().flatten().any(|r| {
+        let ours = u64::from(r["me"].as_bool().unwrap_or(false));
+        r["count"].as_u64().unwrap_or(0) > ours
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedKind {
+    DeleteMessage,
+    RemoveReaction,
+}
+
+/// One API call a real run would make.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlannedAction {
+    pub kind: PlannedKind,
+    pub channel_id: String,
+    pub message_id: String,
+    /// Unix milliseconds, derived from the message snowflake.
+    pub timestamp: u64,
+    pub author_id: Option<String>,
+    pub snippet: String,
+    /// Emoji parameter of a `remove_reaction` action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+    /// Full message, kept when the job archives or downloads media so execution can do the same.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<serde_json::Value>,
+}
+
+impl PlannedAction {
+    /// Actions for one evaluated message: reaction removals first, then the delete, matching a real run.
+    pub fn from_decision(channel_id: &str, message: &serde_json::Value, decision: &MessageDecision, keep_message: bool) -> Vec<Self> {
+        let message_id = message["id"].as_str().unwrap_or_default();
+        let base = Self {
+            kind: PlannedKind::DeleteMessage,
+            channel_id: channel_id.to_string(),
+            message_id: message_id.to_string(),
+            timestamp: snowflake::parse(message_id).map(snowflake::timestamp_ms).unwrap_or_default(),
+            author_id: message["author"]["id"].as_str().map(|s| s.to_string()),
+            snippet: message["content"].as_str().unwrap_or_default().chars().take(SNIPPET_CHARS).collect(),
+            emoji: None,
+            message: None,
+        };
+        let mut actions: Vec<Self> = decision.reactions.iter().map(|emoji| Self {
+            kind: PlannedKind::RemoveReaction,
+            emoji: Some(emoji.clone()),
+            ..base.clone()
+        }).collect();
+        if decision.delete {
+            actions.push(Self { message: keep_message.then(|| message.clone()), ..base });
+        }
+        actions
+    }
+
+    fn key(&self) -> (PlannedKind, String, Option<String>) {
+        (self.kind, self.message_id.clone(), self.emoji.clone())
+    }
+}
+
+/// Output of a simulated job, stored as `<app_local_data_dir>/plans/<job_id>.jsonl`.
+///
+/// # Logic
+/// Actions are appended page by page while the simulation runs, so a resumed simulation may
+/// repeat the tail of a page; duplicates are dropped on load. The plan id is the simulation's job id.
+#[derive(Debug, Serialize, Clone)]
+pub struct PurgePlan {
+    pub plan_id: String,
+    pub filters: PurgeFilters,
+    pub actions: Vec<PlannedAction>,
+    /// Hex SHA-256 of the deduplicated actions as JSON lines. Execution requires the digest the
+    /// user reviewed, so any change to the plan file after review is refused.
+    pub digest: String,
+}
+
+impl PurgePlan {
+    const DIR_NAME: &'static str = "plans";
+
+    fn path(app: &AppHandle, plan_id: &str) -> Result<PathBuf, AppError> {
+        uuid::Uuid::parse_str(plan_id).map_err(|_| AppError {
+            user_message: "Invalid plan identifier.".into(),
+            error_code: "invalid_plan_id".into(),
+            technical_details: Some(plan_id.to_string()),
+        })?;
+        let dir = app.path().app_local_data_dir()?.join(Self::DIR_NAME);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir.join(format!("{}.jsonl", plan_id)))
+    }
+
+    /// Appends actions to the plan file and syncs it to disk.
+    pub fn append(app: &AppHandle, plan_id: &str, actions: &[PlannedAction]) -> Result<(), AppError> {
+        if actions.is_empty() { return Ok(()); }
+        let mut lines = Vec::new();
+        for action in actions {
+            lines.extend(serde_json::to_vec(action)?);
+            lines.push(b'\n');
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(Self::path(app, plan_id)?)?;
+        file.write_all(&lines)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    pub fn load(app: &AppHandle, plan_id: &str, filters: PurgeFilters) -> Result<Self, AppError> {
+        let text = match std::fs::read_to_string(Self::path(app, plan_id)?) {
+            Ok(text) => text,
+            // A simulation that matched nothing never creates the file.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let actions = parse_actions(&text)?;
+        Ok(Self { plan_id: plan_id.to_string(), filters, digest: digest(&actions)?, actions })
+    }
+
+    pub fn delete_count(&self) -> usize {
+        self.actions.iter().filter(|a| a.kind == PlannedKind::DeleteMessage).count()
+    }
+
+    pub fn reaction_count(&self) -> usize {
+        self.actions.iter().filter(|a| a.kind == PlannedKind::RemoveReaction).count()
+    }
+}
+
+fn parse_actions(text: &str) -> Result<Vec<PlannedAction>, AppError> {
+    let mut seen = HashSet::new();
+    let mut actions = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let action: PlannedAction = serde_json::from_str(line)?;
+        if seen.insert(action.key()) {
+            actions.push(action);
+        }
+    }
+    Ok(actions)
+}
+
+fn digest(actions: &[PlannedAction]) -> Result<String, AppError> {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    for action in actions {
+        context.update(&serde_json::to_vec(action)?);
+        context.update(b"\n");
+    }
+    Ok(context.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: u64, author: &str, content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id.to_string(),
+            "author": { "id": author },
+            "content": content,
+            "attachments": [],
+            "reactions": [
+                { "me": true, "emoji": { "id": null, "name": "👍" } },
+                { "me": false, "emoji": { "id": null, "name": "🔥" } },
+                { "me": true, "emoji": { "id": "42", "name": "custom" } },
+            ],
+        })
+    }
+
+    #[test]
+    fn reactions_follow_window_and_attachment_filters_but_not_authorship() {
+        let id = snowflake::from_timestamp_ms(1_700_000_000_000);
+        let filters = PurgeFilters { purge_reactions: true, search_query: Some("Hello".into()), ..Default::default() };
+        let content = filters.compile_content_filter().unwrap();
+
+        let theirs = evaluate(&message(id, "2", "nothing here"), &filters, &content, &SnowflakeWindow::default(), "1", false);
+        assert_eq!(theirs, MessageDecision { reactions: vec!["👍".into(), "custom:42".into()], ..Default::default() });
+
+        let ours = evaluate(&message(id, "1", "say hello"), &filters, &content, &SnowflakeWindow::default(), "1", false);
+        assert!(ours.matched && ours.delete && !ours.skipped_not_ours);
+
+        let outside = SnowflakeWindow::new(Some(1_800_000_000_000), None);
+        assert_eq!(evaluate(&message(id, "1", "hello"), &filters, &content, &outside, "1", false), MessageDecision::default());
+
+        let attachments_only = PurgeFilters { only_attachments: true, ..filters };
+        assert_eq!(evaluate(&message(id, "1", "hello"), &attachments_only, &content, &SnowflakeWindow::default(), "1", false), MessageDecision::default());
+    }
+
+    #[test]
+    fn keep_rules_protect_messages_from_deletion() {
+        let id = snowflake::from_timestamp_ms(1_700_000_000_000);
+        let content = ContentFilter::default();
+        let mut msg = message(id, "1", "hello");
+        let delete = |m: &serde_json::Value, f: &PurgeFilters| evaluate(m, f, &content, &SnowflakeWindow::default(), "1", false).delete;
+
+        // Only our own reactions: nobody else reacted.
+        msg["reactions"] = serde_json::json!([{ "me": true, "count": 1, "emoji": { "id": null, "name": "👍" } }]);
+        let keep_reacted = PurgeFilters { keep_reacted_by_others: true, ..Default::default() };
+        assert!(delete(&msg, &keep_reacted));
+        msg["reactions"][0]["count"] = 2.into();
+        assert!(!delete(&msg, &keep_reacted));
+
+        msg["pinned"] = true.into();
+        assert!(delete(&msg, &PurgeFilters::default()));
+        assert!(!delete(&msg, &PurgeFilters { keep_pinned: true, ..Default::default() }));
+    }
+
+    #[test]
+    fn repeated_plan_lines_are_dropped() {
+        let decision = MessageDecision { matched: true, delete: true, skipped_not_ours: false, reactions: vec!["👍".into()] };
+        let actions = PlannedAction::from_decision("10", &message(99 << 22, "1", "hi"), &decision, false);
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1].kind, PlannedKind::DeleteMessage);
+
+        let line = |a: &PlannedAction| serde_json::to_string(a).unwrap();
+        let text = [line(&actions[0]), line(&actions[1]), line(&actions[1])].join("\n");
+        let parsed = parse_actions(&text).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(digest(&parsed).unwrap(), digest(&actions).unwrap());
+    }
+
+    #[test]
+    fn digest_covers_every_action_field() {
+        let decision = MessageDecision { matched: true, delete: true, skipped_not_ours: false, reactions: vec!["👍".into()] };
+        let actions = PlannedAction::from_decision("10", &message(99 << 22, "1", "hi"), &decision, false);
+        let original = digest(&actions).unwrap();
+        assert_eq!(original.len(), 64);
+
+        let mut retargeted = actions.clone();
+        retargeted[1].channel_id = "11".into();
+        assert_ne!(digest(&retargeted).unwrap(), original);
+        assert_ne!(digest(&actions[..1]).unwrap(), original);
+        assert_ne!(digest(&[actions[1].clone(), actions[0].clone()]).unwrap(), original);
+    }
+}
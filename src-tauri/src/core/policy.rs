@@ -0,0 +1,244 @@
+// src-tauri/src/core/policy.rs
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use crate::core::content_filter::FilterExpr;
+use crate::core::error::AppError;
+use crate::core::journal::PurgeFilters;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Named set of retention rules. Messages older than `max_age_days` are deleted unless one of the
+/// keep rules protects them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub policy_id: String,
+    pub name: String,
+    /// `None` applies the rules to messages of any age.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    #[serde(default)]
+    pub keep_pinned: bool,
+    #[serde(default)]
+    pub keep_reacted_by_others: bool,
+    #[serde(default)]
+    pub keep_attachments: bool,
+    /// Messages matching any of these patterns are kept.
+    #[serde(default)]
+    pub keep_patterns: Vec<FilterExpr>,
+}
+
+impl RetentionPolicy {
+    pub fn validated(mut self) -> Result<Self, AppError> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err(AppError { user_message: "A policy needs a name.".into(), error_code: "invalid_policy".into(), ..Default::default() });
+        }
+        self.filters(0, true).compile_content_filter()?;
+        if self.policy_id.is_empty() {
+            self.policy_id = uuid::Uuid::new_v4().to_string();
+        }
+        Ok(self)
+    }
+
+    /// Purge filters enforcing the policy for a run starting at `now_ms`.
+    pub fn filters(&self, now_ms: u64, simulation: bool) -> PurgeFilters {
+        PurgeFilters {
+            end_time: self.max_age_days.map(|days| now_ms.saturating_sub(days as u64 * DAY_MS)),
+            simulation,
+            exclusions: self.keep_patterns.clone(),
+            keep_pinned: self.keep_pinned,
+            keep_reacted_by_others: self.keep_reacted_by_others,
+            keep_attachments: self.keep_attachments,
+            ..Default::default()
+        }
+    }
+}
+
+/// What a policy is attached to. A DM is addressed by its channel id, like a guild channel.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyTarget {
+    Guild { guild_id: String },
+    Channel { channel_id: String },
+    Dm { channel_id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PolicyAssignment {
+    pub target: PolicyTarget,
+    pub policy_id: String,
+}
+
+/// Every policy and where it is attached. Persisted through `PolicyStore`.
+///
+/// # Logic
+/// A target holds at most one policy. A policy on a channel or DM takes precedence over the one
+/// on its guild, so a community-wide rule can be relaxed or tightened for single channels.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RetentionPolicies {
+    pub policies: Vec<RetentionPolicy>,
+    pub assignments: Vec<PolicyAssignment>,
+}
+
+impl RetentionPolicies {
+    pub fn get(&self, policy_id: &str) -> Result<&RetentionPolicy, AppError> {
+        self.policies.iter().find(|p| p.policy_id == policy_id).ok_or_else(|| AppError {
+            user_message: "Retention policy not found.".into(),
+            error_code: "policy_not_found".into(),
+            technical_details: Some(policy_id.to_string()),
+        })
+    }
+
+    /// Attaches `policy_id` to `target`, replacing its previous policy; `None` detaches it.
+    pub fn assign(&mut self, target: PolicyTarget, policy_id: Option<String>) -> Result<(), AppError> {
+        if let Some(policy_id) = &policy_id {
+            self.get(policy_id)?;
+        }
+        self.assignments.retain(|a| a.target != target);
+        if let Some(policy_id) = policy_id {
+            self.assignments.push(PolicyAssignment { target, policy_id });
+        }
+        Ok(())
+    }
+
+    /// Removes a policy together with its assignments.
+    pub fn remove(&mut self, policy_id: &str) -> Result<(), AppError> {
+        self.get(policy_id)?;
+        self.policies.retain(|p| p.policy_id != policy_id);
+        self.assignments.retain(|a| a.policy_id != policy_id);
+        Ok(())
+    }
+
+    pub fn guild_ids(&self) -> impl Iterator<Item = &str> {
+        self.assignments.iter().filter_map(|a| match &a.target {
+            PolicyTarget::Guild { guild_id } => Some(guild_id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Channels governed by each policy, in policy order. `guild_channels` maps every assigned
+    /// guild to its channel ids; channels with their own assignment are left to that policy.
+    pub fn channel_groups(&self, guild_channels: &HashMap<String, Vec<String>>) -> Vec<(&RetentionPolicy, Vec<String>)> {
+        let mut governing: HashMap<&str, &str> = HashMap::new();
+        for assignment in &self.assignments {
+            if let PolicyTarget::Channel { channel_id } | PolicyTarget::Dm { channel_id } = &assignment.target {
+                governing.insert(channel_id, &assignment.policy_id);
+            }
+        }
+        for assignment in &self.assignments {
+            if let PolicyTarget::Guild { guild_id } = &assignment.target {
+                for channel_id in guild_channels.get(guild_id).into_iter().flatten() {
+                    governing.entry(channel_id).or_insert(&assignment.policy_id);
+                }
+            }
+        }
+
+        self.policies.iter().filter_map(|policy| {
+            let mut channels: Vec<String> = governing.iter()
+                .filter(|(_, policy_id)| **policy_id == policy.policy_id)
+                .map(|(channel_id, _)| channel_id.to_string())
+                .collect();
+            channels.sort();
+            (!channels.is_empty()).then_some((policy, channels))
+        }).collect()
+    }
+}
+
+/// The policy set, stored as `policies.json` in the app local data dir.
+///
+/// # Logic
+/// Every policy command rewrites the whole file; `lock` serializes their read-modify-write cycles
+/// and the file is replaced atomically, so concurrent edits are not lost and a crash mid-write
+/// leaves the previous version in place.
+pub struct PolicyStore {
+    lock: Mutex<()>,
+}
+
+impl PolicyStore {
+    const FILE_NAME: &'static str = "policies.json";
+
+    pub fn new() -> Self {
+        Self { lock: Mutex::new(()) }
+    }
+
+    fn guard(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn path(app: &AppHandle) -> Result<PathBuf, AppError> {
+        Ok(app.path().app_local_data_dir()?.join(Self::FILE_NAME))
+    }
+
+    /// Reads the stored policies, falling back to an empty set when the file does not exist yet.
+    fn read(app: &AppHandle) -> Result<RetentionPolicies, AppError> {
+        match std::fs::read(Self::path(app)?) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RetentionPolicies::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(app: &AppHandle, set: &RetentionPolicies) -> Result<(), AppError> {
+        let path = Self::path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(set)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn load(&self, app: &AppHandle) -> Result<RetentionPolicies, AppError> {
+        let _guard = self.guard();
+        Self::read(app)
+    }
+
+    /// Applies `edit` to the stored set and writes it back, returning whatever `edit` returns.
+    /// Nothing is written if `edit` fails.
+    pub fn update<T>(&self, app: &AppHandle, edit: impl FnOnce(&mut RetentionPolicies) -> Result<T, AppError>) -> Result<T, AppError> {
+        let _guard = self.guard();
+        let mut set = Self::read(app)?;
+        let result = edit(&mut set)?;
+        Self::write(app, &set)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(id: &str) -> RetentionPolicy {
+        RetentionPolicy { policy_id: id.into(), name: id.into(), max_age_days: Some(30), keep_pinned: true, keep_reacted_by_others: false, keep_attachments: false, keep_patterns: Vec::new() }
+    }
+
+    #[test]
+    fn channel_assignments_override_their_guild() {
+        let mut set = RetentionPolicies { policies: vec![policy("strict"), policy("lenient")], assignments: Vec::new() };
+        set.assign(PolicyTarget::Guild { guild_id: "g".into() }, Some("strict".into())).unwrap();
+        set.assign(PolicyTarget::Channel { channel_id: "c2".into() }, Some("lenient".into())).unwrap();
+        set.assign(PolicyTarget::Dm { channel_id: "dm".into() }, Some("lenient".into())).unwrap();
+        assert_eq!(set.assign(PolicyTarget::Dm { channel_id: "dm".into() }, Some("missing".into())).unwrap_err().error_code, "policy_not_found");
+
+        let guild_channels = HashMap::from([("g".to_string(), vec!["c1".to_string(), "c2".to_string()])]);
+        let groups: Vec<(&str, Vec<String>)> = set.channel_groups(&guild_channels).into_iter().map(|(p, c)| (p.policy_id.as_str(), c)).collect();
+        assert_eq!(groups, vec![("strict", vec!["c1".to_string()]), ("lenient", vec!["c2".to_string(), "dm".to_string()])]);
+
+        set.remove("lenient").unwrap();
+        assert_eq!(set.assignments.len(), 1);
+    }
+
+    #[test]
+    fn max_age_becomes_the_window_end() {
+        let filters = policy("p").filters(40 * DAY_MS, false);
+        assert_eq!(filters.end_time, Some(10 * DAY_MS));
+        assert!(filters.keep_pinned && !filters.simulation);
+    }
+}
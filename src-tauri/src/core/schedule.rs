@@ -0,0 +1,320 @@
+// src-tauri/src/core/schedule.rs
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+use crate::core::error::AppError;
+use crate::core::journal::{JobStatus, PurgeFilters};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Parsed five-field cron expression: `minute hour day-of-month month day-of-week`.
+///
+/// # Logic
+/// Fields accept `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`).
+/// Day-of-week runs from 0 (Sunday) to 6, with 7 also meaning Sunday. As in classic cron, when
+/// both day fields are restricted a day matches if *either* does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Searching stops after this long, so an expression that never fires (`0 0 31 2 *`) has no next run.
+    const HORIZON_DAYS: i64 = 5 * 366;
+
+    pub fn parse(expression: &str) -> Result<Self, AppError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid_cron(expression, "expected 5 fields"));
+        };
+        let mut weekdays = parse_field(expression, weekday, 0, 7)?;
+        // 7 is an alias of Sunday.
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(expression, minute, 0, 59)?,
+            hours: parse_field(expression, hour, 0, 23)?,
+            days: parse_field(expression, day, 1, 31)?,
+            months: parse_field(expression, month, 1, 12)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    fn matches_day(&self, time: &NaiveDateTime) -> bool {
+        let day = self.days & (1 << time.day()) != 0;
+        let weekday = self.weekdays & (1 << time.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First matching minute strictly after `after`.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(Self::HORIZON_DAYS);
+        let mut time = start;
+        while time < limit {
+            if self.months & (1 << time.month()) == 0 {
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = chrono::NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(&time) {
+                time = (time.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+fn invalid_cron(expression: &str, detail: &str) -> AppError {
+    AppError {
+        user_message: format!("Invalid schedule '{}'.", expression),
+        error_code: "invalid_schedule".into(),
+        technical_details: Some(detail.to_string()),
+    }
+}
+
+/// Bitset of the values in `[min, max]` selected by one cron field.
+fn parse_field(expression: &str, field: &str, min: u32, max: u32) -> Result<u64, AppError> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| invalid_cron(expression, part))?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let parse = |v: &str| v.parse::<u32>().ok().filter(|v| (min..=max).contains(v)).ok_or_else(|| invalid_cron(expression, part));
+                let start = parse(start)?;
+                // `5/15` means "from 5 to the end of the range, every 15".
+                let end = if step > 1 && !range.contains('-') { max } else { parse(end)? };
+                (start, end)
+            }
+        };
+        if start > end {
+            return Err(invalid_cron(expression, part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// Outcome of the latest run of a schedule.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleRun {
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// Journal of the purge job, absent when the run could not start.
+    pub job_id: Option<String>,
+    pub status: Option<JobStatus>,
+    pub deleted: u64,
+    pub failed: u64,
+    pub error: Option<String>,
+}
+
+/// A purge job that runs on a cron schedule while the app is open.
+///
+/// # Logic
+/// `older_than_days` and `newer_than_days` are resolved against the start time of each run and
+/// replace the `start_time`/`end_time` of `filters`, so "older than 30 days" keeps rolling forward.
+/// A run missed while the app was closed is made up once at the next start.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurgeSchedule {
+    #[serde(default)]
+    pub schedule_id: String,
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Five-field cron expression, evaluated in local time.
+    pub cron: String,
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub filters: PurgeFilters,
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+    #[serde(default)]
+    pub newer_than_days: Option<u32>,
+    /// Identity the schedule was created under; runs are skipped while another one is active.
+    #[serde(default)]
+    pub identity_id: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+    /// Unix milliseconds of the next run, `None` when disabled or the expression never fires.
+    #[serde(default)]
+    pub next_run: Option<i64>,
+    #[serde(default)]
+    pub last_run: Option<ScheduleRun>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Next run as Unix milliseconds. Times skipped by a DST change do not exist locally and are passed over.
+fn next_local(cron: &CronSchedule, mut after: NaiveDateTime) -> Option<i64> {
+    loop {
+        let next = cron.next_after(after)?;
+        if let Some(time) = next.and_local_timezone(chrono::Local).earliest() {
+            return Some(time.timestamp_millis());
+        }
+        after = next;
+    }
+}
+
+impl PurgeSchedule {
+    /// Checks the definition and fills in the derived fields of a new or edited schedule.
+    pub fn validated(mut self) -> Result<Self, AppError> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() || self.channel_ids.is_empty() {
+            return Err(AppError { user_message: "A schedule needs a name and at least one channel.".into(), error_code: "invalid_schedule".into(), ..Default::default() });
+        }
+        if self.older_than_days.zip(self.newer_than_days).is_some_and(|(older, newer)| newer <= older) {
+            return Err(AppError { user_message: "The retention window is empty.".into(), error_code: "invalid_schedule".into(), ..Default::default() });
+        }
+        self.filters.compile_content_filter()?;
+        CronSchedule::parse(&self.cron)?;
+        if self.schedule_id.is_empty() {
+            self.schedule_id = uuid::Uuid::new_v4().to_string();
+            self.created_at = chrono::Utc::now().timestamp_millis();
+        }
+        self.reschedule(chrono::Local::now().naive_local());
+        Ok(self)
+    }
+
+    /// Sets `next_run` to the first matching minute after `now` (local time).
+    pub fn reschedule(&mut self, now: NaiveDateTime) {
+        self.next_run = match CronSchedule::parse(&self.cron) {
+            Ok(cron) if self.enabled => next_local(&cron, now),
+            _ => None,
+        };
+    }
+
+    pub fn is_due(&self, now_ms: i64) -> bool {
+        self.enabled && self.next_run.is_some_and(|next| next <= now_ms)
+    }
+
+    /// Filters for a run starting at `now_ms`, with the retention window made absolute.
+    pub fn resolve_filters(&self, now_ms: u64) -> PurgeFilters {
+        let mut filters = self.filters.clone();
+        if let Some(days) = self.older_than_days {
+            filters.end_time = Some(now_ms.saturating_sub(days as u64 * DAY_MS));
+        }
+        if let Some(days) = self.newer_than_days {
+            filters.start_time = Some(now_ms.saturating_sub(days as u64 * DAY_MS));
+        }
+        filters
+    }
+}
+
+/// Schedule definitions, stored as `schedules.json` in the app local data dir.
+///
+/// # Logic
+/// The UI and the scheduler loop both rewrite the file; `lock` serializes their read-modify-write
+/// cycles, and `changed` wakes the loop so edits take effect without waiting for the next tick.
+pub struct ScheduleStore {
+    lock: Mutex<()>,
+    pub changed: Notify,
+}
+
+impl ScheduleStore {
+    const FILE_NAME: &'static str = "schedules.json";
+
+    pub fn new() -> Self {
+        Self { lock: Mutex::new(()), changed: Notify::new() }
+    }
+
+    fn guard(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn path(app: &AppHandle) -> Result<PathBuf, AppError> {
+        Ok(app.path().app_local_data_dir()?.join(Self::FILE_NAME))
+    }
+
+    fn read(app: &AppHandle) -> Result<Vec<PurgeSchedule>, AppError> {
+        match std::fs::read(Self::path(app)?) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(app: &AppHandle, schedules: &[PurgeSchedule]) -> Result<(), AppError> {
+        let path = Self::path(app)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(schedules)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn list(&self, app: &AppHandle) -> Result<Vec<PurgeSchedule>, AppError> {
+        let _guard = self.guard();
+        Self::read(app)
+    }
+
+    /// Applies `edit` to the stored list and writes it back, returning whatever `edit` returns.
+    pub fn update<T>(&self, app: &AppHandle, edit: impl FnOnce(&mut Vec<PurgeSchedule>) -> Result<T, AppError>) -> Result<T, AppError> {
+        let _guard = self.guard();
+        let mut schedules = Self::read(app)?;
+        let result = edit(&mut schedules)?;
+        Self::write(app, &schedules)?;
+        self.changed.notify_one();
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn next_run_follows_the_expression() {
+        let nightly = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(nightly.next_after(at("2026-03-10 01:00")), Some(at("2026-03-10 02:30")));
+        assert_eq!(nightly.next_after(at("2026-03-10 02:30")), Some(at("2026-03-11 02:30")));
+
+        let quarter_hourly = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+        // 2026-03-14 is a Saturday.
+        assert_eq!(quarter_hourly.next_after(at("2026-03-13 17:50")), Some(at("2026-03-16 09:00")));
+
+        // Both day fields restricted: the 1st of the month or any Sunday.
+        let either = CronSchedule::parse("0 0 1 * 7").unwrap();
+        assert_eq!(either.next_after(at("2026-03-10 00:00")), Some(at("2026-03-15 00:00")));
+
+        assert_eq!(CronSchedule::parse("0 0 31 2 *").unwrap().next_after(at("2026-01-01 00:00")), None);
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for expression in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"] {
+            assert_eq!(CronSchedule::parse(expression).unwrap_err().error_code, "invalid_schedule", "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+// src-tauri/src/core/settings.rs
+
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use tauri::{AppHandle, Manager};
+use crate::core::error::AppError;
+
+/// Which storage engine backs the `Vault`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultBackendKind {
+    /// Use the OS keychain when it responds, the encrypted file otherwise.
+    #[default]
+    Auto,
+    Keyring,
+    File,
+}
+
+/// OAuth2 client preferences shared by the browser (`start_oauth_flow`) and RPC flows.
+///
+/// # Logic
+/// The browser flow binds the first free port in `[callback_port_start, callback_port_end]`.
+/// Every port in that range must be registered as a redirect URI in the Discord developer portal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OAuthSettings {
+    pub callback_port_start: u16,
+    pub callback_port_end: u16,
+    /// Scopes requested in addition to `identify` and `guilds` (e.g. `guilds.members.read`, `messages.read`).
+    pub extra_scopes: Vec<String>,
+}
+
+impl Default for OAuthSettings {
+    fn default() -> Self {
+        Self { callback_port_start: 58123, callback_port_end: 58123, extra_scopes: Vec::new() }
+    }
+}
+
+impl OAuthSettings {
+    const BASE_SCOPES: [&'static str; 2] = ["identify", "guilds"];
+
+    /// Redirect URI registered for the RPC login, which binds no listener. It must stay port-less
+    /// to match the URI existing applications have registered for it.
+    pub const RPC_REDIRECT_URI: &'static str = "http://127.0.0.1";
+
+    /// Redirect URI for the OAuth callback listener on `port`.
+    pub fn redirect_uri(port: u16) -> String {
+        format!("http://127.0.0.1:{}", port)
+    }
+
+    pub fn ports(&self) -> std::ops::RangeInclusive<u16> {
+        self.callback_port_start..=self.callback_port_end
+    }
+
+    /// Base scopes followed by the configured extras, without duplicates.
+    pub fn scopes(&self) -> Vec<String> {
+        let mut scopes: Vec<String> = Self::BASE_SCOPES.iter().map(|s| s.to_string()).collect();
+        for scope in &self.extra_scopes {
+            if !scopes.contains(scope) {
+                scopes.push(scope.clone());
+            }
+        }
+        scopes
+    }
+
+    /// Normalizes user input and rejects ranges or scopes Discord would refuse.
+    pub fn validated(mut self) -> Result<Self, AppError> {
+        if self.callback_port_start < 1024 || self.callback_port_start > self.callback_port_end {
+            return Err(AppError {
+                user_message: "The callback port range must be ascending and above 1023.".into(),
+                error_code: "invalid_settings".into(),
+                ..Default::default()
+            });
+        }
+        self.extra_scopes = self.extra_scopes.iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        if let Some(bad) = self.extra_scopes.iter().find(|s| s.chars().any(|c| c.is_whitespace())) {
+            return Err(AppError {
+                user_message: format!("Invalid OAuth scope '{}'.", bad),
+                error_code: "invalid_settings".into(),
+                ..Default::default()
+            });
+        }
+        Ok(self)
+    }
+}
+
+/// Non-secret application preferences, stored as `settings.json` in the app local data dir.
+/// Anything sensitive belongs in the `Vault` instead.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    pub vault_backend: VaultBackendKind,
+    pub oauth: OAuthSettings,
+}
+
+impl AppSettings {
+    const FILE_NAME: &'static str = "settings.json";
+
+    fn path(app: &AppHandle) -> Result<PathBuf, AppError> {
+        Ok(app.path().app_local_data_dir()?.join(Self::FILE_NAME))
+    }
+
+    /// Loads the stored settings, falling back to defaults when the file does not exist yet.
+    pub fn load(app: &AppHandle) -> Result<Self, AppError> {
+        match std::fs::read(Self::path(app)?) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), AppError> {
+        let path = Self::path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
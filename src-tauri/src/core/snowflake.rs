@@ -0,0 +1,96 @@
+// src-tauri/src/core/snowflake.rs
+
+/// Milliseconds between the Unix epoch and the Discord epoch (2015-01-01T00:00:00Z).
+pub const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// Parses a snowflake from its string form as returned by the API.
+pub fn parse(id: &str) -> Option<u64> {
+    id.parse().ok()
+}
+
+/// Creation time of a snowflake in Unix milliseconds.
+pub fn timestamp_ms(id: u64) -> u64 {
+    (id >> 22) + DISCORD_EPOCH_MS
+}
+
+/// Returns the smallest snowflake that can be generated at `ms` (Unix milliseconds).
+/// Times past the 42-bit timestamp range clamp to `u64::MAX` instead of wrapping around.
+pub fn from_timestamp_ms(ms: u64) -> u64 {
+    let offset = ms.saturating_sub(DISCORD_EPOCH_MS);
+    if offset >> 42 != 0 { u64::MAX } else { offset << 22 }
+}
+
+/// Snowflake bounds of a `[start_time, end_time]` window (Unix milliseconds, both inclusive).
+///
+/// # Logic
+/// `min_id` is the first snowflake of `start_time`; `max_id` is the first snowflake *after*
+/// `end_time`, which makes it directly usable as an exclusive `before` query parameter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnowflakeWindow {
+    pub min_id: Option<u64>,
+    pub max_id: Option<u64>,
+}
+
+impl SnowflakeWindow {
+    pub fn new(start_time: Option<u64>, end_time: Option<u64>) -> Self {
+        Self {
+            min_id: start_time.map(from_timestamp_ms),
+            max_id: end_time.map(|end| from_timestamp_ms(end.saturating_add(1))),
+        }
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.min_id.is_none_or(|min| id >= min) && self.max_id.is_none_or(|max| id < max)
+    }
+
+    /// True if `id` is older than the window, i.e. everything before it can be skipped.
+    pub fn is_before_window(&self, id: u64) -> bool {
+        self.min_id.is_some_and(|min| id < min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_round_trip_and_clamp() {
+        assert_eq!(parse("175928847299117063"), Some(175928847299117063));
+        assert_eq!(parse("not-a-snowflake"), None);
+        assert_eq!(parse(""), None);
+
+        // Documented example: 175928847299117063 was created at 2016-04-30T11:18:25.796Z.
+        assert_eq!(timestamp_ms(175928847299117063), 1_462_015_105_796);
+        assert_eq!(timestamp_ms(from_timestamp_ms(1_462_015_105_796)), 1_462_015_105_796);
+        assert!(from_timestamp_ms(1_462_015_105_796) <= 175928847299117063);
+
+        assert_eq!(from_timestamp_ms(DISCORD_EPOCH_MS), 0);
+        assert_eq!(from_timestamp_ms(0), 0);
+        assert_eq!(timestamp_ms(0), DISCORD_EPOCH_MS);
+
+        let last_ms = DISCORD_EPOCH_MS + (1 << 42) - 1;
+        assert_eq!(timestamp_ms(from_timestamp_ms(last_ms)), last_ms);
+        assert_eq!(from_timestamp_ms(last_ms + 1), u64::MAX);
+        assert_eq!(from_timestamp_ms(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn window_edges() {
+        let (start, end) = (1_700_000_000_000, 1_700_000_060_000);
+        let window = SnowflakeWindow::new(Some(start), Some(end));
+
+        // Both bounds are inclusive to the millisecond, whatever the low 22 bits hold.
+        assert!(window.contains(from_timestamp_ms(start)));
+        assert!(!window.contains(from_timestamp_ms(start) - 1));
+        assert!(window.contains(from_timestamp_ms(end) | ((1 << 22) - 1)));
+        assert!(!window.contains(from_timestamp_ms(end + 1)));
+
+        assert!(window.is_before_window(from_timestamp_ms(start) - 1));
+        assert!(!window.is_before_window(from_timestamp_ms(start)));
+        assert!(!window.is_before_window(from_timestamp_ms(end + 1)));
+
+        let open = SnowflakeWindow::default();
+        assert!(open.contains(0) && open.contains(u64::MAX));
+        assert!(!open.is_before_window(0));
+    }
+}
@@ -1,9 +1,11 @@
 // src-tauri/src/core/vault.rs
 
-use tauri::AppHandle;
-use keyring::Entry;
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager};
 use serde::{Serialize, Deserialize};
 use crate::core::error::AppError;
+use crate::core::settings::{AppSettings, VaultBackendKind};
+use crate::core::vault_backend::{VaultBackend, KeyringBackend, EncryptedFileBackend};
 
 /// Represents a stored Discord identity, containing the unique user ID, 
 /// the current session token, and the authentication protocol used (OAuth vs User Token).
@@ -13,85 +15,265 @@ pub struct DiscordIdentity {
     pub username: String,
     pub token: String,
     pub is_oauth: bool,
+    /// OAuth refresh token used to renew `token` before it expires.
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Unix milliseconds at which the OAuth access token expires.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// Unix milliseconds of the last successful `/users/@me` validation.
+    #[serde(default)]
+    pub last_validated: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    #[serde(rename = "oauth")]
+    OAuth,
+    #[serde(rename = "user_token")]
+    UserToken,
+}
+
+/// Token-free view of a `DiscordIdentity`. This is the only identity shape handed to the webview.
+#[derive(Debug, Serialize, Clone)]
+pub struct PublicIdentity {
+    pub id: String,
+    pub username: String,
+    pub avatar: Option<String>,
+    pub auth_method: AuthMethod,
+    pub last_validated: Option<i64>,
+}
+
+impl From<&DiscordIdentity> for PublicIdentity {
+    fn from(identity: &DiscordIdentity) -> Self {
+        Self {
+            id: identity.id.clone(),
+            username: identity.username.clone(),
+            avatar: identity.avatar.clone(),
+            auth_method: if identity.is_oauth { AuthMethod::OAuth } else { AuthMethod::UserToken },
+            last_validated: identity.last_validated,
+        }
+    }
+}
+
+/// Managed state holding the storage engine selected for this session.
+pub struct VaultState {
+    backend: RwLock<Arc<dyn VaultBackend>>,
+    file_backend: Arc<EncryptedFileBackend>,
+}
+
+/// Backend description returned to the UI.
+#[derive(Debug, Serialize, Clone)]
+pub struct VaultStatus {
+    pub backend: &'static str,
+    pub configured: VaultBackendKind,
+    pub unlocked: bool,
+}
+
+impl VaultState {
+    /// Environment variable allowing headless hosts to unlock the encrypted file vault at startup.
+    const PASSPHRASE_ENV: &'static str = "DISCORD_PURGE_VAULT_PASSPHRASE";
+    const FILE_NAME: &'static str = "vault.enc";
+
+    pub fn init(app: &AppHandle) -> Result<Self, AppError> {
+        let path = app.path().app_local_data_dir()?.join(Self::FILE_NAME);
+        let file_backend = Arc::new(EncryptedFileBackend::new(path));
+        if let Ok(passphrase) = std::env::var(Self::PASSPHRASE_ENV) {
+            if let Err(e) = file_backend.unlock(&passphrase) {
+                tracing::warn!("[Vault] Failed to unlock file vault from environment: {}", e);
+            }
+        }
+
+        let kind = AppSettings::load(app)?.vault_backend;
+        let state = Self { backend: RwLock::new(file_backend.clone()), file_backend };
+        state.select(kind);
+        Ok(state)
+    }
+
+    /// Switches the active backend according to `kind`, probing the keychain for `Auto`.
+    pub fn select(&self, kind: VaultBackendKind) {
+        let keyring = KeyringBackend::new(Vault::SERVICE_NAME);
+        let backend: Arc<dyn VaultBackend> = match kind {
+            VaultBackendKind::Keyring => Arc::new(keyring),
+            VaultBackendKind::File => self.file_backend.clone(),
+            VaultBackendKind::Auto if keyring.is_available() => Arc::new(keyring),
+            VaultBackendKind::Auto => self.file_backend.clone(),
+        };
+        tracing::info!("[Vault] Using '{}' backend ({:?})", backend.name(), kind);
+        *self.backend.write().unwrap_or_else(|e| e.into_inner()) = backend;
+    }
+
+    pub fn unlock_file(&self, passphrase: &str) -> Result<(), AppError> {
+        self.file_backend.unlock(passphrase)
+    }
+
+    fn backend(&self) -> Arc<dyn VaultBackend> {
+        self.backend.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
 }
 
 /// The Vault is the primary security interface for sensitive data persistence.
 /// It utilizes the host OS keychain (Windows Credential Manager, macOS Keychain, or Secret Service)
 /// to ensure that Discord tokens and application credentials never reside in plain text on the disk.
+/// Where no keychain is reachable it falls back to a passphrase-encrypted file (see `VaultState`).
 pub struct Vault;
 
 impl Vault {
     const SERVICE_NAME: &'static str = "com.discordprivacy.util";
+    const INDEX_KEY: &'static str = "identity_index";
+    const ACTIVE_KEY: &'static str = "active_account";
+
+    fn backend(app: &AppHandle) -> Arc<dyn VaultBackend> {
+        app.state::<VaultState>().backend()
+    }
+
+    /// Reports which backend is serving the vault and whether it is usable.
+    pub fn status(app: &AppHandle) -> Result<VaultStatus, AppError> {
+        let backend = Self::backend(app);
+        Ok(VaultStatus {
+            backend: backend.name(),
+            configured: AppSettings::load(app)?.vault_backend,
+            unlocked: backend.is_unlocked(),
+        })
+    }
 
     /// Persists a Discord identity to the secure OS vault.
     /// 
     /// # Logic
     /// Encodes the `DiscordIdentity` struct as a JSON string before storage.
     /// Uses the user's Discord ID as the unique account identifier.
-    pub fn save_identity(_app: &AppHandle, identity: DiscordIdentity) -> Result<(), AppError> {
-        let entry = Entry::new(Self::SERVICE_NAME, &format!("account_{}", identity.id))?;
+    /// Re-saving an identity with an unchanged access token keeps its stored refresh token and expiry.
+    pub fn save_identity(app: &AppHandle, mut identity: DiscordIdentity) -> Result<(), AppError> {
+        let backend = Self::backend(app);
+        if identity.refresh_token.is_none() {
+            if let Ok(stored) = Self::get_identity(app, &identity.id) {
+                if stored.token == identity.token {
+                    identity.refresh_token = stored.refresh_token;
+                    identity.expires_at = stored.expires_at;
+                }
+            }
+        }
         let secret = serde_json::to_string(&identity)?;
-        entry.set_password(&secret)?;
+        backend.set(&format!("account_{}", identity.id), &secret)?;
+
+        let mut index = Self::read_index(backend.as_ref())?;
+        if !index.contains(&identity.id) {
+            index.push(identity.id.clone());
+            Self::write_index(backend.as_ref(), &index)?;
+        }
         
         // Also track this as the most recent 'active' account
-        let active_entry = Entry::new(Self::SERVICE_NAME, "active_account")?;
-        active_entry.set_password(&identity.id)?;
+        backend.set(Self::ACTIVE_KEY, &identity.id)?;
         Ok(())
     }
 
-    /// Retrieves the currently active Discord token and its type.
-    /// 
-    /// # Returns
-    /// A tuple of `(token_string, is_bearer_token)`.
-    pub fn get_active_token(_app: &AppHandle) -> Result<(String, bool), AppError> {
-        let active_entry = Entry::new(Self::SERVICE_NAME, "active_account")?;
-        let id = active_entry.get_password().map_err(|_| AppError { 
+    /// Retrieves the full record of the active identity, including its tokens.
+    /// Raw tokens leave the vault only through this and `get_identity`; commands obtain their
+    /// credentials via `auth::session::active_token`, which also renews expiring OAuth tokens.
+    pub(crate) fn get_active_session(app: &AppHandle) -> Result<DiscordIdentity, AppError> {
+        Self::get_identity(app, &Self::get_active_id(app)?)
+    }
+
+    /// Replaces the OAuth token pair of a stored identity after a refresh.
+    /// Discord may omit the refresh token from a refresh response, in which case the old one stays valid.
+    pub fn update_tokens(app: &AppHandle, id: &str, access_token: &str, refresh_token: Option<&str>, expires_at: Option<i64>) -> Result<(), AppError> {
+        let mut identity = Self::get_identity(app, id)?;
+        identity.token = access_token.to_string();
+        if let Some(refresh_token) = refresh_token {
+            identity.refresh_token = Some(refresh_token.to_string());
+        }
+        identity.expires_at = expires_at;
+        Self::backend(app).set(&format!("account_{}", id), &serde_json::to_string(&identity)?)
+    }
+
+    /// Fetches the public view of the identity currently marked as active.
+    pub fn get_active_identity(app: &AppHandle) -> Result<PublicIdentity, AppError> {
+        Ok(PublicIdentity::from(&Self::get_identity(app, &Self::get_active_id(app)?)?))
+    }
+
+    /// Marks a stored identity as the active session.
+    pub fn set_active_identity(app: &AppHandle, id: &str) -> Result<(), AppError> {
+        let backend = Self::backend(app);
+        if !Self::read_index(backend.as_ref())?.iter().any(|i| i == id) {
+            return Err(AppError { user_message: "Identity not found.".into(), error_code: "identity_not_found".into(), ..Default::default() });
+        }
+        backend.set(Self::ACTIVE_KEY, id)
+    }
+
+    fn get_active_id(app: &AppHandle) -> Result<String, AppError> {
+        Self::backend(app).get(Self::ACTIVE_KEY)?.ok_or_else(|| AppError { 
             user_message: "No active session found. Please login.".into(), 
             error_code: "no_active_session".into(),
             ..Default::default()
-        })?;
-        
-        let identity = Self::get_identity(_app, &id)?;
-        Ok((identity.token, identity.is_oauth))
+        })
     }
 
-    /// Fetches a specific identity from the vault by its Discord ID.
-    pub fn get_identity(_app: &AppHandle, id: &str) -> Result<DiscordIdentity, AppError> {
-        let entry = Entry::new(Self::SERVICE_NAME, &format!("account_{}", id))?;
-        let secret = entry.get_password()?;
+    /// Fetches a specific identity from the vault by its Discord ID, tokens included.
+    pub(crate) fn get_identity(app: &AppHandle, id: &str) -> Result<DiscordIdentity, AppError> {
+        let secret = Self::backend(app).get(&format!("account_{}", id))?.ok_or_else(|| AppError {
+            user_message: "Identity not found.".into(),
+            error_code: "identity_not_found".into(),
+            ..Default::default()
+        })?;
         Ok(serde_json::from_str(&secret)?)
     }
 
     /// Lists all Discord identities currently stored in the system vault.
     /// 
+    /// # Logic
+    /// OS keychains cannot enumerate entries portably, so the vault keeps its own index
+    /// (a JSON list of account ids) under `identity_index`. Ids whose entry can no longer
+    /// be read are skipped rather than failing the whole listing.
+    ///
     /// # Performance
     /// This operation is performed synchronously during identity-switch tasks.
-    pub fn list_identities(_app: &AppHandle) -> Vec<DiscordIdentity> {
-        // Implementation simplified: in a production environment, we would maintain
-        // an index of keys. For this utility, we iterate through common patterns.
-        // For MVP, we fetch the known accounts.
-        // Note: Real key discovery requires OS-specific enumerators.
-        Vec::new() // Placeholder for the actual list logic
+    pub fn list_identities(app: &AppHandle) -> Result<Vec<PublicIdentity>, AppError> {
+        Ok(Self::read_index(Self::backend(app).as_ref())?
+            .iter()
+            .filter_map(|id| Self::get_identity(app, id).ok())
+            .map(|identity| PublicIdentity::from(&identity))
+            .collect())
     }
 
     /// Removes an identity from the vault, permanently destroying the token link.
-    pub fn remove_identity(_app: &AppHandle, id: &str) -> Result<(), AppError> {
-        let entry = Entry::new(Self::SERVICE_NAME, &format!("account_{}", id))?;
-        entry.delete_credential()?;
+    /// Clears the active session as well if it pointed at the removed identity.
+    pub fn remove_identity(app: &AppHandle, id: &str) -> Result<(), AppError> {
+        let backend = Self::backend(app);
+        backend.delete(&format!("account_{}", id))?;
+
+        let mut index = Self::read_index(backend.as_ref())?;
+        if index.iter().any(|i| i == id) {
+            index.retain(|i| i != id);
+            Self::write_index(backend.as_ref(), &index)?;
+        }
+
+        if backend.get(Self::ACTIVE_KEY)?.as_deref() == Some(id) {
+            backend.delete(Self::ACTIVE_KEY)?;
+        }
         Ok(())
     }
 
+    fn read_index(backend: &dyn VaultBackend) -> Result<Vec<String>, AppError> {
+        match backend.get(Self::INDEX_KEY)? {
+            Some(raw) => Ok(serde_json::from_str(&raw)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn write_index(backend: &dyn VaultBackend, ids: &[String]) -> Result<(), AppError> {
+        backend.set(Self::INDEX_KEY, &serde_json::to_string(ids)?)
+    }
+
     /// Stores a raw application credential (like Client ID or Secret).
-    pub fn set_credential(_app: &AppHandle, key: &str, value: &str) -> Result<(), AppError> {
-        let entry = Entry::new(Self::SERVICE_NAME, key)?;
-        entry.set_password(value)?;
-        Ok(())
+    pub fn set_credential(app: &AppHandle, key: &str, value: &str) -> Result<(), AppError> {
+        Self::backend(app).set(key, value)
     }
 
     /// Retrieves a raw application credential.
-    pub fn get_credential(_app: &AppHandle, key: &str) -> Result<String, AppError> {
-        let entry = Entry::new(Self::SERVICE_NAME, key)?;
-        entry.get_password().map_err(|_| AppError { 
+    pub fn get_credential(app: &AppHandle, key: &str) -> Result<String, AppError> {
+        Self::backend(app).get(key)?.ok_or_else(|| AppError { 
             user_message: format!("Credential '{}' not found. Please complete Setup.", key), 
             error_code: "credentials_missing".into(),
             ..Default::default()
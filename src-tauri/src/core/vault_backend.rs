@@ -0,0 +1,295 @@
+// src-tauri/src/core/vault_backend.rs
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use keyring::Entry;
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Serialize, Deserialize};
+use crate::core::error::AppError;
+
+/// Storage engine behind the `Vault`: a flat string key/value store for secrets.
+pub trait VaultBackend: Send + Sync {
+    /// Short identifier reported to the UI (`keyring` / `file`).
+    fn name(&self) -> &'static str;
+    /// False while the backend needs a passphrase before it can serve requests.
+    fn is_unlocked(&self) -> bool;
+    fn get(&self, key: &str) -> Result<Option<String>, AppError>;
+    fn set(&self, key: &str, value: &str) -> Result<(), AppError>;
+    fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Host OS keychain (Windows Credential Manager, macOS Keychain, or Secret Service).
+pub struct KeyringBackend {
+    service: &'static str,
+}
+
+impl KeyringBackend {
+    pub fn new(service: &'static str) -> Self {
+        Self { service }
+    }
+
+    /// Probes the keychain with a write, a read through a separate entry and a delete. Only a store
+    /// that gives back what was written counts: a read-only store, a locked one, or keyring's
+    /// in-memory fallback (which forgets values between entries, and everything on restart) does not.
+    pub fn is_available(&self) -> bool {
+        const PROBE: &str = "backend_probe";
+        let round_trip = || -> Result<bool, keyring::Error> {
+            let marker = uuid::Uuid::new_v4().to_string();
+            let entry = Entry::new(self.service, PROBE)?;
+            entry.set_password(&marker)?;
+            let read = Entry::new(self.service, PROBE)?.get_password();
+            let _ = entry.delete_credential();
+            Ok(read? == marker)
+        };
+        round_trip().unwrap_or(false)
+    }
+}
+
+impl VaultBackend for KeyringBackend {
+    fn name(&self) -> &'static str { "keyring" }
+
+    fn is_unlocked(&self) -> bool { true }
+
+    fn get(&self, key: &str) -> Result<Option<String>, AppError> {
+        match Entry::new(self.service, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), AppError> {
+        Entry::new(self.service, key)?.set_password(value)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), AppError> {
+        match Entry::new(self.service, key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// On-disk layout of the encrypted vault file. Every write re-encrypts the whole map with a fresh nonce.
+#[derive(Serialize, Deserialize)]
+struct SealedVault {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct UnlockedVault {
+    key: LessSafeKey,
+    salt: Vec<u8>,
+    entries: HashMap<String, String>,
+}
+
+/// AES-256-GCM encrypted JSON map stored in the app data directory, for hosts without a usable keychain.
+///
+/// # Logic
+/// The key is derived from a user passphrase with PBKDF2-HMAC-SHA256 and a random per-file salt.
+/// The backend starts locked and only serves requests after `unlock` succeeds.
+pub struct EncryptedFileBackend {
+    path: PathBuf,
+    state: Mutex<Option<UnlockedVault>>,
+}
+
+impl EncryptedFileBackend {
+    const VERSION: u32 = 1;
+    const PBKDF2_ITERATIONS: u32 = 210_000;
+    const AAD: &'static [u8] = b"com.discordprivacy.util/vault/v1";
+
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, state: Mutex::new(None) }
+    }
+
+    /// Derives the key from `passphrase` and decrypts the vault, creating an empty one on first use.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), AppError> {
+        if passphrase.is_empty() {
+            return Err(AppError { user_message: "The vault passphrase cannot be empty.".into(), error_code: "vault_passphrase_invalid".into(), ..Default::default() });
+        }
+
+        let unlocked = match std::fs::read(&self.path) {
+            Ok(data) => {
+                let sealed: SealedVault = serde_json::from_slice(&data)?;
+                let salt = decode(&sealed.salt)?;
+                let key = Self::derive_key(passphrase, &salt)?;
+                let nonce: [u8; aead::NONCE_LEN] = decode(&sealed.nonce)?.try_into().map_err(|_| corrupted())?;
+                let mut buffer = decode(&sealed.ciphertext)?;
+                let plaintext = key.open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(Self::AAD), &mut buffer)
+                    .map_err(|_| AppError { user_message: "Incorrect vault passphrase.".into(), error_code: "vault_passphrase_invalid".into(), ..Default::default() })?;
+                let entries = serde_json::from_slice(plaintext)?;
+                UnlockedVault { key, salt, entries }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut salt = vec![0u8; 16];
+                SystemRandom::new().fill(&mut salt).map_err(|_| crypto_failure())?;
+                let key = Self::derive_key(passphrase, &salt)?;
+                let vault = UnlockedVault { key, salt, entries: HashMap::new() };
+                self.persist(&vault)?;
+                vault
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        *self.lock_state() = Some(unlocked);
+        Ok(())
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey, AppError> {
+        let iterations = NonZeroU32::new(Self::PBKDF2_ITERATIONS).ok_or_else(crypto_failure)?;
+        let mut key_bytes = [0u8; 32];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key_bytes);
+        let key = UnboundKey::new(&aead::AES_256_GCM, &key_bytes).map_err(|_| crypto_failure())?;
+        Ok(LessSafeKey::new(key))
+    }
+
+    fn persist(&self, vault: &UnlockedVault) -> Result<(), AppError> {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| crypto_failure())?;
+        let mut buffer = serde_json::to_vec(&vault.entries)?;
+        vault.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(Self::AAD), &mut buffer)
+            .map_err(|_| crypto_failure())?;
+
+        let sealed = SealedVault {
+            version: Self::VERSION,
+            salt: BASE64.encode(&vault.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(&buffer),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&sealed)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, Option<UnlockedVault>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn with_unlocked<T>(&self, f: impl FnOnce(&mut UnlockedVault) -> Result<T, AppError>) -> Result<T, AppError> {
+        let mut state = self.lock_state();
+        let vault = state.as_mut().ok_or_else(|| AppError {
+            user_message: "The encrypted vault is locked. Enter your vault passphrase.".into(),
+            error_code: "vault_locked".into(),
+            ..Default::default()
+        })?;
+        f(vault)
+    }
+}
+
+impl VaultBackend for EncryptedFileBackend {
+    fn name(&self) -> &'static str { "file" }
+
+    fn is_unlocked(&self) -> bool {
+        self.lock_state().is_some()
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, AppError> {
+        self.with_unlocked(|vault| Ok(vault.entries.get(key).cloned()))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), AppError> {
+        self.with_unlocked(|vault| {
+            vault.entries.insert(key.to_string(), value.to_string());
+            self.persist(vault)
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<(), AppError> {
+        self.with_unlocked(|vault| {
+            if vault.entries.remove(key).is_some() {
+                self.persist(vault)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, AppError> {
+    BASE64.decode(value).map_err(|_| corrupted())
+}
+
+fn corrupted() -> AppError {
+    AppError { user_message: "The encrypted vault file is corrupted.".into(), error_code: "vault_corrupted".into(), ..Default::default() }
+}
+
+fn crypto_failure() -> AppError {
+    AppError { user_message: "Vault encryption failed.".into(), error_code: "vault_crypto_error".into(), ..Default::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault_path(dir: &tempfile::TempDir) -> PathBuf {
+        dir.path().join("vault.enc")
+    }
+
+    #[test]
+    fn entries_survive_reopening_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = EncryptedFileBackend::new(vault_path(&dir));
+        backend.unlock("correct horse").unwrap();
+        backend.set("token", "secret-value").unwrap();
+        backend.set("other", "x").unwrap();
+        backend.delete("other").unwrap();
+        assert_eq!(backend.get("token").unwrap().as_deref(), Some("secret-value"));
+
+        let raw = std::fs::read_to_string(vault_path(&dir)).unwrap();
+        assert!(!raw.contains("secret-value"));
+
+        let reopened = EncryptedFileBackend::new(vault_path(&dir));
+        assert!(!reopened.is_unlocked());
+        reopened.unlock("correct horse").unwrap();
+        assert_eq!(reopened.get("token").unwrap().as_deref(), Some("secret-value"));
+        assert_eq!(reopened.get("other").unwrap(), None);
+    }
+
+    #[test]
+    fn a_locked_vault_refuses_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = EncryptedFileBackend::new(vault_path(&dir));
+        assert_eq!(backend.get("token").unwrap_err().error_code, "vault_locked");
+        assert_eq!(backend.set("token", "value").unwrap_err().error_code, "vault_locked");
+        assert_eq!(backend.delete("token").unwrap_err().error_code, "vault_locked");
+        assert_eq!(backend.unlock("").unwrap_err().error_code, "vault_passphrase_invalid");
+    }
+
+    #[test]
+    fn wrong_passphrases_and_tampering_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = EncryptedFileBackend::new(vault_path(&dir));
+        backend.unlock("correct horse").unwrap();
+        backend.set("token", "secret-value").unwrap();
+
+        let wrong = EncryptedFileBackend::new(vault_path(&dir));
+        assert_eq!(wrong.unlock("battery staple").unwrap_err().error_code, "vault_passphrase_invalid");
+        assert!(!wrong.is_unlocked());
+
+        // Flip one byte of the ciphertext: the GCM tag no longer verifies.
+        let mut sealed: SealedVault = serde_json::from_slice(&std::fs::read(vault_path(&dir)).unwrap()).unwrap();
+        let mut ciphertext = BASE64.decode(&sealed.ciphertext).unwrap();
+        ciphertext[0] ^= 0x01;
+        sealed.ciphertext = BASE64.encode(&ciphertext);
+        std::fs::write(vault_path(&dir), serde_json::to_vec(&sealed).unwrap()).unwrap();
+        let tampered = EncryptedFileBackend::new(vault_path(&dir));
+        assert!(tampered.unlock("correct horse").is_err());
+        assert!(!tampered.is_unlocked());
+
+        sealed.nonce = "not base64!".into();
+        std::fs::write(vault_path(&dir), serde_json::to_vec(&sealed).unwrap()).unwrap();
+        assert_eq!(EncryptedFileBackend::new(vault_path(&dir)).unlock("correct horse").unwrap_err().error_code, "vault_corrupted");
+    }
+}
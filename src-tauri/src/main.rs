@@ -8,8 +8,13 @@ use tauri::Manager;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use tokio::sync::mpsc;
-use crate::api::rate_limiter::{RateLimiterActor, ApiHandle};
+use std::sync::Arc;
+use futures_util::future::BoxFuture;
+use crate::api::rate_limiter::{RateLimiterActor, ApiHandle, RenewToken};
 use crate::core::op_manager::OperationManager;
+use crate::core::policy::PolicyStore;
+use crate::core::schedule::ScheduleStore;
+use crate::core::vault::VaultState;
 
 fn main() {
     if let Err(e) = rustls::crypto::ring::default_provider().install_default() {
@@ -41,7 +46,12 @@ fn main() {
             info!("Application starting up...");
 
             let (tx, rx) = mpsc::channel(100);
-            let mut rate_limiter = RateLimiterActor::new(rx, app.handle().clone()); 
+            let renew_app = app.handle().clone();
+            let renew: RenewToken = Arc::new(move |stale_token: String| -> BoxFuture<'static, Option<String>> {
+                let app = renew_app.clone();
+                Box::pin(async move { crate::auth::session::renew(&app, &stale_token).await.ok().flatten() })
+            });
+            let mut rate_limiter = RateLimiterActor::new(rx, app.handle().clone(), renew);
             let api_handle = ApiHandle::new(tx);
             
             tauri::async_runtime::spawn(async move {
@@ -50,12 +60,19 @@ fn main() {
 
             app.manage(api_handle);
 
+            let vault_state = VaultState::init(app.handle())?;
+            app.manage(vault_state);
+
             let op_manager = OperationManager::new();
             app.manage(op_manager);
 
             let auth_state = auth::AuthState::default();
             app.manage(auth_state);
 
+            app.manage(PolicyStore::new());
+            app.manage(ScheduleStore::new());
+            tauri::async_runtime::spawn(api::schedule::run_scheduler(app.handle().clone()));
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -70,11 +87,39 @@ fn main() {
             auth::list_identities,
             auth::switch_identity,
             auth::remove_identity,
+            auth::logout,
+            auth::get_vault_status,
+            auth::unlock_vault,
+            auth::set_vault_backend,
+            auth::get_oauth_settings,
+            auth::set_oauth_settings,
             api::discord::fetch_guilds,
             api::discord::fetch_channels,
             api::discord::fetch_relationships,
+            api::discord::open_dm,
+            api::discord::purge_dms_with_users,
             api::discord::fetch_preview_messages,
+            api::export::export_channel_history,
+            api::package::import_data_package,
+            api::package::get_package_summary,
+            api::package::get_package_messages,
+            api::package::purge_package_channels,
+            api::plan::get_plan,
+            api::plan::export_plan,
+            api::plan::execute_plan,
+            api::schedule::list_schedules,
+            api::schedule::save_schedule,
+            api::schedule::delete_schedule,
+            api::policy::get_retention_policies,
+            api::policy::save_retention_policy,
+            api::policy::delete_retention_policy,
+            api::policy::assign_retention_policy,
+            api::policy::apply_retention_policies,
             api::discord::bulk_delete_messages,
+            api::discord::delete_message_ids,
+            api::discord::resume_job,
+            api::discord::list_resumable_jobs,
+            api::discord::get_job_report,
             api::discord::bulk_leave_guilds,
             api::discord::bulk_remove_relationships,
             api::discord::stealth_privacy_wipe,
@@ -84,7 +129,8 @@ fn main() {
             api::discord::pause_operation,
             api::discord::resume_operation,
             api::discord::abort_operation,
-            api::discord::get_operation_status
+            api::discord::get_operation_status,
+            api::discord::list_jobs
         ])
         .run(tauri::generate_context!())
         .unwrap_or_else(|e| panic!("error while running tauri application: {:?}", e));
@@ -82,15 +82,15 @@ export const OperationOverlay = ({
 
               <div className="w-full flex justify-center gap-4 mt-8">
                 {operationStatus.is_paused ? (
-                  <button onClick={onResume} className="m3-button-primary !bg-m3-secondary !text-m3-onSecondary">
+                  <button onClick={() => onResume()} className="m3-button-primary !bg-m3-secondary !text-m3-onSecondary">
                     <Play className="w-5 h-5" /> Resume
                   </button>
                 ) : (
-                  <button onClick={onPause} className="m3-button-primary">
+                  <button onClick={() => onPause()} className="m3-button-primary">
                     <Pause className="w-5 h-5" /> Pause
                   </button>
                 )}
-                <button onClick={onAbort} className="m3-button-outlined !border-m3-error !text-m3-error">
+                <button onClick={() => onAbort()} className="m3-button-outlined !border-m3-error !text-m3-error">
                   <Square className="w-5 h-5" /> Abort
                 </button>
               </div>
@@ -48,7 +48,7 @@ export const Sidebar = ({
               </div>
               <div className="flex-1 min-w-0">
                 <p className="text-[11px] font-black truncate uppercase italic">{id.username}</p>
-                <p className="text-[8px] opacity-50 uppercase tracking-widest">{id.is_oauth ? 'OFFICIAL' : 'BYPASS'}</p>
+                <p className="text-[8px] opacity-50 uppercase tracking-widest">{id.auth_method === 'oauth' ? 'OFFICIAL' : 'BYPASS'}</p>
               </div>
               {user?.id === id.id && <div className="w-1.5 h-1.5 rounded-full bg-m3-primary animate-pulse" />}
             </button>
@@ -147,6 +147,7 @@ export const MessagesMode = ({
                     <div className="flex items-center gap-3">
                       <Hash className="w-3.5 h-3.5" />
                       <span className="text-xs font-bold uppercase italic">{c.name}</span>
+                      {c.parent_name && <span className="text-[10px] opacity-60 italic">in #{c.parent_name}</span>}
                     </div>
                     {selectedChannels.has(c.id) && <Eye className="w-3.5 h-3.5 animate-pulse text-m3-primary" />}
                   </button>
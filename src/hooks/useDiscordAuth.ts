@@ -1,7 +1,7 @@
 import { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../store/authStore';
-import { DiscordStatus, DiscordIdentity, DiscordUser } from '../types/discord';
+import { DiscordStatus, DiscordIdentity, DiscordUser, RemovalReport } from '../types/discord';
 
 /**
  * Custom hook for managing the Discord authentication lifecycle.
@@ -82,6 +82,14 @@ export const useDiscordAuth = () => {
   }, []);
 
   const handleLogout = async () => { 
+    try {
+      const report = await invoke<RemovalReport>('logout');
+      if (report.revocation === 'failed') {
+        console.warn(`[Auth] Session removed locally but Discord revocation failed: ${report.revocation_error}`);
+      }
+    } catch (err) {
+      console.error("Logout failed:", err);
+    }
     reset(); 
     setView('manual'); 
   };
@@ -28,6 +28,7 @@ export const useDiscordOperations = (handleApiError: (err: any, fallback: string
   const [operationStatus, setOperationStatus] = useState<OperationStatus>({ 
     is_running: false, is_paused: false, should_abort: false 
   });
+  const [jobs, setJobs] = useState<OperationStatus[]>([]);
 
   const fetchGuilds = useCallback(async () => {
     setLoading(true); 
@@ -53,7 +54,10 @@ export const useDiscordOperations = (handleApiError: (err: any, fallback: string
 
   const getOperationStatus = useCallback(async () => {
     try { 
-      setOperationStatus(await invoke('get_operation_status')); 
+      const running = await invoke<OperationStatus[]>('list_jobs');
+      setJobs(running);
+      // The overlay follows the most recently started operation.
+      setOperationStatus(running[running.length - 1] ?? { is_running: false, is_paused: false, should_abort: false });
     } catch (err) { 
       console.error("Failed to get op status:", err); 
     }
@@ -150,9 +154,9 @@ export const useDiscordOperations = (handleApiError: (err: any, fallback: string
     else fetchPreview(id);
   };
 
-  const handlePause = async () => { await invoke('pause_operation'); getOperationStatus(); };
-  const handleResume = async () => { await invoke('resume_operation'); getOperationStatus(); };
-  const handleAbort = async () => { await invoke('abort_operation'); getOperationStatus(); setIsProcessing(false); setProgress(null); };
+  const handlePause = async (jobId = operationStatus.job_id) => { if (jobId) await invoke('pause_operation', { jobId }); getOperationStatus(); };
+  const handleResume = async (jobId = operationStatus.job_id) => { if (jobId) await invoke('resume_operation', { jobId }); getOperationStatus(); };
+  const handleAbort = async (jobId = operationStatus.job_id) => { if (jobId) await invoke('abort_operation', { jobId }); getOperationStatus(); setIsProcessing(false); setProgress(null); };
 
   const handleBuryAuditLog = async () => {
     if (selectedGuilds.size === 0 || selectedChannels.size === 0) {
@@ -207,12 +211,14 @@ export const useDiscordOperations = (handleApiError: (err: any, fallback: string
         let start = timeRange === '24h' ? now - 86400000 : timeRange === '7d' ? now - 604800000 : undefined;
         await invoke('bulk_delete_messages', { 
           channelIds: Array.from(selectedChannels), 
-          startTime: start, 
-          endTime: undefined, 
-          searchQuery: searchQuery || undefined, 
-          purgeReactions, 
-          simulation, 
-          onlyAttachments 
+          filters: {
+            start_time: start, 
+            end_time: undefined, 
+            search_query: searchQuery || undefined, 
+            purge_reactions: purgeReactions, 
+            simulation, 
+            only_attachments: onlyAttachments 
+          }
         });
       } else if (mode === 'servers') {
         await invoke('bulk_leave_guilds', { guildIds: Array.from(selectedGuildsToLeave) });
@@ -242,6 +248,7 @@ export const useDiscordOperations = (handleApiError: (err: any, fallback: string
     onlyAttachments, setOnlyAttachments,
     simulation, setSimulation,
     operationStatus, setOperationStatus,
+    jobs,
     fetchGuilds,
     fetchRelationships,
     getOperationStatus,
@@ -21,6 +21,8 @@ export interface Channel {
   id: string;
   name: string;
   channel_type: number;
+  parent_id?: string;
+  parent_name?: string;
 }
 
 export interface Relationship {
@@ -37,19 +39,229 @@ export interface Relationship {
 export interface DiscordIdentity {
   id: string;
   username: string;
-  is_oauth: boolean;
+  avatar?: string;
+  auth_method: 'oauth' | 'user_token';
+  last_validated?: number;
+}
+
+export interface RemovalReport {
+  id: string;
+  revocation: 'not_applicable' | 'revoked' | 'failed';
+  revocation_error?: string;
+}
+
+export type MessageOutcome = 'deleted' | 'simulated' | 'skipped_not_ours' | 'forbidden' | 'already_gone' | 'failed';
+
+export interface MessageTarget {
+  channel_id: string;
+  message_id: string;
+}
+
+export interface TargetedDeleteReport {
+  deleted: number;
+  already_gone: number;
+  forbidden: number;
+  failed: number;
+  reactions_removed: number;
+  reactions_failed: number;
+  aborted: boolean;
+}
+
+export type FilterExpr =
+  | { type: 'contains'; value: string }
+  | { type: 'word'; value: string }
+  | { type: 'regex'; pattern: string; case_sensitive?: boolean }
+  | { type: 'all'; terms: FilterExpr[] }
+  | { type: 'any'; terms: FilterExpr[] }
+  | { type: 'not'; term: FilterExpr };
+
+export interface PlannedAction {
+  kind: 'delete_message' | 'remove_reaction';
+  channel_id: string;
+  message_id: string;
+  timestamp: number;
+  author_id: string | null;
+  snippet: string;
+  emoji?: string;
+  message?: unknown;
+}
+
+export interface PurgePlan {
+  plan_id: string;
+  filters: Record<string, unknown>;
+  actions: PlannedAction[];
+  digest: string;
+}
+
+export interface PlanReady {
+  plan_id: string;
+  status: JobReport['status'];
+  delete_count: number;
+  reaction_count: number;
+  digest: string;
+}
+
+export interface ActionError {
+  message_id: string;
+  action: 'delete' | 'reaction';
+  status: number | null;
+  detail: string | null;
+}
+
+export interface ChannelReport {
+  channel_id: string;
+  before: string | null;
+  completed: boolean;
+  scanned: number;
+  matched: number;
+  deleted: number;
+  skipped: number;
+  forbidden: number;
+  not_found: number;
+  failed: number;
+  archived: number;
+  reactions_removed: number;
+  rate_limited_retries: number;
+  errors: ActionError[];
+}
+
+export interface JobReport {
+  job_id: string;
+  status: 'running' | 'aborted' | 'interrupted' | 'completed';
+  simulation: boolean;
+  created_at: number;
+  finished_at: number;
+  scanned: number;
+  matched: number;
+  deleted: number;
+  forbidden: number;
+  not_found: number;
+  failed: number;
+  rate_limited_retries: number;
+  channels: ChannelReport[];
+}
+
+export interface ScheduleRun {
+  started_at: number;
+  finished_at: number;
+  job_id: string | null;
+  status: JobReport['status'] | null;
+  deleted: number;
+  failed: number;
+  error: string | null;
+}
+
+export interface PurgeSchedule {
+  /** Empty when creating a schedule. */
+  schedule_id: string;
+  name: string;
+  enabled: boolean;
+  /** Five-field cron expression in local time, e.g. `0 3 * * *`. */
+  cron: string;
+  channel_ids: string[];
+  filters: Record<string, unknown>;
+  older_than_days?: number | null;
+  newer_than_days?: number | null;
+  identity_id?: string | null;
+  created_at?: number;
+  next_run?: number | null;
+  last_run?: ScheduleRun | null;
+}
+
+export interface RetentionPolicy {
+  /** Empty when creating a policy. */
+  policy_id: string;
+  name: string;
+  max_age_days?: number | null;
+  keep_pinned: boolean;
+  keep_reacted_by_others: boolean;
+  keep_attachments: boolean;
+  keep_patterns: FilterExpr[];
+}
+
+export type PolicyTarget =
+  | { type: 'guild'; guild_id: string }
+  | { type: 'channel'; channel_id: string }
+  | { type: 'dm'; channel_id: string };
+
+export interface RetentionPolicies {
+  policies: RetentionPolicy[];
+  assignments: { target: PolicyTarget; policy_id: string }[];
+}
+
+export interface PolicyRun {
+  policy_id: string;
+  name: string;
+  channel_ids: string[];
+  /** Null when the job could not start because another destructive operation was running. */
+  job_id: string | null;
+  status: JobReport['status'] | null;
+  deleted: number;
+  error: string | null;
 }
 
 export interface Progress {
+  job_id?: string;
   current: number;
   total: number;
   id: string;
+  message_id?: string;
+  /** Set by per-message events; purges report running totals every few messages instead. */
+  outcome?: MessageOutcome;
   deleted_count?: number;
+  skipped_count?: number;
+  forbidden_count?: number;
+  failed_count?: number;
   status: string;
 }
 
 export interface OperationStatus {
+  job_id?: string;
+  kind?: string;
+  destructive?: boolean;
+  started_at?: number;
   is_running: boolean;
   is_paused: boolean;
   should_abort: boolean;
+  current?: number;
+  total?: number;
+}
+
+export interface OAuthSettings {
+  callback_port_start: number;
+  callback_port_end: number;
+  extra_scopes: string[];
+}
+
+export interface OAuthCallbackReady {
+  port: number;
+  redirect_uri: string;
+}
+
+export interface ExportResult {
+  channel_id: string;
+  channel_name: string;
+  directory: string;
+  message_count: number;
+  files: string[];
+}
+
+export interface PackageChannelSummary {
+  channel_id: string;
+  channel_type?: number;
+  name?: string;
+  guild_id?: string;
+  guild_name?: string;
+  message_count: number;
+  first_message_at?: number;
+  last_message_at?: number;
+}
+
+export interface PackageSummary {
+  source: string;
+  imported_at: number;
+  channel_count: number;
+  guild_count: number;
+  message_count: number;
+  channels: PackageChannelSummary[];
 }